@@ -68,9 +68,21 @@ export class ToolkitViewProvider implements WebviewViewProvider {
         console.log(message);
         switch (message.command) {
           case "get_open_files": {
-            // get filepaths of compiled .json files for whichever solidity files are open in the editor
-            let compiledContracts = await helpers.getCompiledForOpenFiles();
-            await this.sendOpenFilesToRust(compiledContracts);
+            // get the paths of whichever solidity files are open in the editor
+            await this.sendOpenFilesToRust(helpers.getOpenSolidityPaths());
+            break;
+          }
+
+          case "get_artifact_files": {
+            const { artifactPaths, buildInfos, storageLayouts } = await helpers.getArtifactFiles();
+            await this.sendArtifactFilesToRust(artifactPaths, buildInfos, storageLayouts);
+            break;
+          }
+
+          // loaded on demand, see helpers.getArtifactCodes
+          case "get_artifact_codes": {
+            const artifactCodes = await helpers.getArtifactCodes(message.data.artifactPaths);
+            await this.sendArtifactCodesToRust(artifactCodes);
             break;
           }
 
@@ -86,6 +98,13 @@ export class ToolkitViewProvider implements WebviewViewProvider {
             break;
           }
 
+          // same file as get_compiled_solidity, but it shouldn't become the selected contract
+          case "get_artifact": {
+            const contents: string = (await helpers.loadFile(Uri.parse(message.data.filePath))).toString();
+            await this.sendArtifactToRust(contents, message.data.filePath);
+            break;
+          }
+
           // TODO: handle this better
           case "forge_build": {
             // hacky way for wasm side to know when a vscode terminal command completes
@@ -159,6 +178,21 @@ export class ToolkitViewProvider implements WebviewViewProvider {
     this._view?.webview.postMessage({ command: "post_open_file_paths", content: solFiles });
   }
 
+  private async sendArtifactFilesToRust(artifactPaths: string[], buildInfos: string[], storageLayouts: string[]) {
+    this._view?.webview.postMessage({
+      command: "post_artifact_files",
+      content: {
+        artifactPaths: artifactPaths,
+        buildInfos: buildInfos,
+        storageLayouts: storageLayouts,
+      },
+    });
+  }
+
+  private async sendArtifactCodesToRust(artifactCodes: string[]) {
+    this._view?.webview.postMessage({ command: "post_artifact_codes", content: artifactCodes });
+  }
+
   private async sendFileContentsToRust(fileContents: string) {
     // console.log("in sendfilecontentstorust");
     this._view?.webview.postMessage({
@@ -174,6 +208,12 @@ export class ToolkitViewProvider implements WebviewViewProvider {
       content: { compiledJson: compiledJson, filePath: filePath },
     });
   }
+  private async sendArtifactToRust(artifactJson: string, filePath: string) {
+    this._view?.webview.postMessage({
+      command: "post_artifact",
+      content: { artifactJson: artifactJson, filePath: filePath },
+    });
+  }
   private async sendCompletedCompileNotifToRust() {
     console.log("in sendCompletedCompileNotif");
     this._view?.webview.postMessage({
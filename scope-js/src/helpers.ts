@@ -1,43 +1,111 @@
 import * as vscode from "vscode";
 import { Uri } from "vscode";
 
-export async function getCompiledForOpenFiles(): Promise<string[]> {
-  let contracts: string[] = [];
+// Workspace relative paths (e.g. src/nested/Token.sol) of the .sol files open in the editor
+export function getOpenSolidityPaths(): string[] {
+  return vscode.window.tabGroups.all
+    .flatMap(({ tabs }) => tabs.map((tab) => tab.input))
+    .filter((input): input is vscode.TabInputText => input instanceof vscode.TabInputText)
+    .map((input) => vscode.workspace.asRelativePath(input.uri, false))
+    .filter((filePath) => filePath.endsWith(".sol"));
+}
+
+// Every artifact .json under the output dirs, plus the `source path -> contract names` listing
+// of each build-info file (trimmed here since the full files can be huge) and the
+// `source:Contract -> storageLayout` of any contracts built with that output.
+// The actual index is built on the rust side.
+export async function getArtifactFiles(): Promise<{
+  artifactPaths: string[];
+  buildInfos: string[];
+  storageLayouts: string[];
+}> {
+  const artifactPaths: string[] = [];
+  const buildInfos: string[] = [];
+  const storageLayouts: string[] = [];
 
   if (!vscode.workspace.workspaceFolders) {
-    return contracts; // You may throw an error or log a message here
+    return { artifactPaths, buildInfos, storageLayouts };
   }
 
-  // Get the open .sol files
-  const openFiles = vscode.window.tabGroups.all
-    .flatMap(({ tabs }) => tabs.map((tab) => tab.label))
-    .filter((filename) => filename.endsWith(".sol"));
-
-  // Function to find and filter compiled contracts
-  const findAndFilterCompiledContracts = async (dirUri: Uri, contractName: string) => {
+  const walk = async (dirUri: Uri) => {
+    let entries: [string, vscode.FileType][];
     try {
-      const compiledContracts = (await vscode.workspace.fs.readDirectory(dirUri))
-        .filter(([name, type]) => type === vscode.FileType.File && name.endsWith(".json"))
-        .map(([name, _]) => Uri.joinPath(dirUri, name).toString());
-      return compiledContracts;
+      entries = await vscode.workspace.fs.readDirectory(dirUri);
     } catch (e) {
-      return [];
+      return; // dir doesn't exist (yet)
+    }
+
+    for (const [name, type] of entries) {
+      const entryUri = Uri.joinPath(dirUri, name);
+      if (type === vscode.FileType.Directory) {
+        await walk(entryUri);
+      } else if (type === vscode.FileType.File && name.endsWith(".json")) {
+        if (dirUri.path.endsWith("/build-info")) {
+          try {
+            const buildInfo = JSON.parse((await loadFile(entryUri)).toString());
+            const contracts = buildInfo.output?.contracts ?? {};
+            const sourceContracts = Object.fromEntries(
+              Object.entries(contracts).map(([sourcePath, byName]) => [sourcePath, Object.keys(byName as object)])
+            );
+            buildInfos.push(JSON.stringify(sourceContracts));
+
+            const layouts = Object.fromEntries(
+              Object.entries(contracts).flatMap(([sourcePath, byName]) =>
+                Object.entries(byName as Record<string, any>)
+                  .filter(([, contract]) => contract.storageLayout)
+                  .map(([contractName, contract]) => [`${sourcePath}:${contractName}`, contract.storageLayout])
+              )
+            );
+            storageLayouts.push(JSON.stringify(layouts));
+          } catch (e) {
+            console.log("couldn't read build-info", entryUri.toString(), e);
+          }
+        } else {
+          artifactPaths.push(entryUri.toString());
+        }
+      }
     }
   };
 
   // Directories to check
   const directories = ["out", "artifacts/.foundry"];
-
-  // Iterate over directories and collect compiled contracts for open .sol files
   for (const dir of directories) {
-    for (const openFile of openFiles) {
-      const dirUri: Uri = Uri.joinPath(vscode.workspace.workspaceFolders[0].uri, `${dir}/${openFile}`);
-      const contractFiles = await findAndFilterCompiledContracts(dirUri, openFile);
-      contracts = [...new Set([...contracts, ...contractFiles])];
-    }
+    await walk(Uri.joinPath(vscode.workspace.workspaceFolders[0].uri, dir));
   }
 
-  return contracts;
+  return { artifactPaths, buildInfos, storageLayouts };
+}
+
+// The ABI of each of the given artifacts, plus for those with runtime code the code and the
+// byte ranges that differ once deployed (immutables + library links), for identifying on-chain
+// code. Interfaces and abstract contracts are included for their selectors/errors.
+// Only asked for once something needs it, so indexing stays cheap.
+export async function getArtifactCodes(artifactPaths: string[]): Promise<string[]> {
+  const artifactCodes: string[] = [];
+  for (const artifactPath of artifactPaths) {
+    try {
+      const artifact = JSON.parse((await loadFile(Uri.parse(artifactPath))).toString());
+      const deployed = artifact.deployedBytecode ?? {};
+      const hasCode = deployed.object && deployed.object !== "0x";
+      const ranges: any[] = hasCode
+        ? [
+            ...Object.values(deployed.immutableReferences ?? {}).flat(),
+            ...Object.values(deployed.linkReferences ?? {}).flatMap((byName) => Object.values(byName as object).flat()),
+          ]
+        : [];
+      artifactCodes.push(
+        JSON.stringify({
+          artifactPath: artifactPath,
+          deployedBytecode: hasCode ? deployed.object : "",
+          masked: ranges.map(({ start, length }) => [start, length]),
+          abi: artifact.abi ?? [],
+        })
+      );
+    } catch (e) {
+      console.log("couldn't read artifact", artifactPath, e);
+    }
+  }
+  return artifactCodes;
 }
 
 export async function loadFile(uri: vscode.Uri): Promise<Uint8Array> {
@@ -1,95 +1,74 @@
 use egui::epaint::ahash::HashMap;
 use ethers::{
-    abi::{ParamType, Token},
-    types::U256,
+    abi::{Abi, Function, ParamType, RawLog, Token},
+    contract::ContractError,
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, MiddlewareError, Provider, RpcError, Ws, WsClientError},
+    signers::LocalWallet,
+    types::{Bytes, Log, Selector, I256, U256},
 };
 use eyre::{eyre, Result};
 use serde_json::Value;
 
-use num_bigint::{BigInt, BigUint};
+use num_bigint::{BigInt, Sign};
 use num_traits::Num;
 
 // TODO: switch to alloy?
 
+// Function inputs/outputs are keyed by 4 byte selector (0x-prefixed hex) rather than name
+// so that overloaded functions don't collide
 pub fn encode_fn_call_to_tokens(
-    func_name: String,
+    selector: String,
     abi: Value,
     func_param_input: HashMap<String, HashMap<String, String>>,
 ) -> Result<Vec<Token>> {
     // create ethers contract object for this contract
-    let abi: ethers::abi::Abi = serde_json::from_value(abi.clone()).unwrap();
-
-    // This will probably run into issues if there are multiple fns w/ the same name;
-    // should switch to using selectors
-    let func = abi
-        .functions
-        .get(func_name.as_str())
-        .expect("Failed to get function args")
-        .get(0)
-        .expect("Failed to get function args");
-
-    let mut tokens: Vec<Token> = Vec::new();
-
-    for param in func.inputs.iter() {
-        let param_name = param.name.clone();
-        let param_kind = param.kind.clone();
-        // let param_internal_type = param.internal_type.clone();
+    let abi: ethers::abi::Abi = serde_json::from_value(abi.clone())?;
+    let func = get_function_by_selector(&abi, &selector)?;
 
-        if let Some(input_value) = func_param_input
-            .get(&func_name)
-            .and_then(|param_map| param_map.get(&param_name))
-        {
-            let token = parse_input_to_token(param_kind, input_value.clone())?;
-
-            tokens.push(token);
-        }
-    }
-
-    Ok(tokens)
+    inputs_to_tokens(func, &selector, &func_param_input)
 }
 
 // TODO - alloy?
 pub fn encode_fn_call_to_calldata(
-    func_name: String,
+    selector: String,
     abi: &Value,
     func_param_input: &HashMap<String, HashMap<String, String>>,
 ) -> Result<String> {
     // create ethers contract object for this contract
-    let abi: ethers::abi::Abi = serde_json::from_value(abi.clone()).unwrap();
-    let base = ethers::contract::BaseContract::from(abi);
-
-    // This will probably run into issues if there are multiple fns w/ the same name;
-    // should switch to using selectors
-    let func = base
-        .abi()
-        .functions
-        .get(func_name.as_str())
-        .expect("Failed to get function args")
-        .get(0)
-        .expect("Failed to get function args");
+    let abi: ethers::abi::Abi = serde_json::from_value(abi.clone())?;
+    let func = get_function_by_selector(&abi, &selector)?;
+
+    let tokens = inputs_to_tokens(func, &selector, func_param_input)?;
+    let calldata = func.encode_input(&tokens)?;
 
+    Ok(format!("0x{}", hex::encode(calldata)))
+}
+
+fn inputs_to_tokens(
+    func: &Function,
+    selector: &str,
+    func_param_input: &HashMap<String, HashMap<String, String>>,
+) -> Result<Vec<Token>> {
     let mut tokens: Vec<Token> = Vec::new();
 
-    //
     for param in func.inputs.iter() {
         let param_name = param.name.clone();
         let param_kind = param.kind.clone();
         // let param_internal_type = param.internal_type.clone();
 
         if let Some(input_value) = func_param_input
-            .get(&func_name)
+            .get(selector)
             .and_then(|param_map| param_map.get(&param_name))
         {
-            let token = parse_input_to_token(param_kind, input_value.clone())?;
+            let token = parse_input_to_token(param_kind, input_value.clone())
+                .map_err(|e| eyre!("{}: {}", param_name, e))?;
 
             tokens.push(token);
         }
     }
 
-    Ok(base
-        .encode(&func_name, &tokens[..])
-        .expect("Failed to encode")
-        .to_string())
+    Ok(tokens)
 }
 
 pub fn constructor_args_to_tokens(abi: Value, args: Vec<String>) -> Result<Vec<Token>> {
@@ -100,7 +79,8 @@ pub fn constructor_args_to_tokens(abi: Value, args: Vec<String>) -> Result<Vec<T
         for (idx, param) in constructor.inputs.iter().enumerate() {
             let param_name = param.name.clone();
             let param_kind = param.kind.clone();
-            let token = parse_input_to_token(param_kind, args[idx].clone())?;
+            let token = parse_input_to_token(param_kind, args[idx].clone())
+                .map_err(|e| eyre!("{}: {}", param_name, e))?;
 
             tokens.push(token);
         }
@@ -109,27 +89,219 @@ pub fn constructor_args_to_tokens(abi: Value, args: Vec<String>) -> Result<Vec<T
 }
 
 pub fn decode_return_values(
-    func_name: String,
+    selector: String,
     abi: &Value,
-    return_data: &ethers::types::Bytes,
+    return_data: &Bytes,
 ) -> Result<Vec<Token>> {
     // create ethers contract object for this contract
-    let abi: ethers::abi::Abi = serde_json::from_value(abi.clone()).unwrap();
-    let base = ethers::contract::BaseContract::from(abi);
-
-    // This will probably run into issues if there are multiple fns w/ the same name;
-    // should switch to using selectors
-    let func = base
-        .abi()
-        .functions
-        .get(func_name.as_str())
-        .expect("Failed to get function args")
-        .get(0)
-        .expect("Failed to get function args");
+    let abi: ethers::abi::Abi = serde_json::from_value(abi.clone())?;
+    let func = get_function_by_selector(&abi, &selector)?;
 
     func.decode_output(return_data).map_err(eyre::Report::from)
 }
 
+// Pulls the revert data out of a JSON-RPC error response, if the error was a revert. Covers
+// both transports, errors from the signer (e.g. its gas estimate while filling a tx) which wrap
+// the provider's error, and contract calls made through either.
+pub fn revert_data_from_error(error: &eyre::Report) -> Option<Bytes> {
+    if let Some(ws_error) = error.downcast_ref::<WsClientError>() {
+        return RpcError::as_error_response(ws_error)?.as_revert_data();
+    }
+    // Provider<_>::Error is ProviderError, whichever the transport
+    middleware_revert_data::<Provider<Http>>(error)
+        .or_else(|| middleware_revert_data::<Provider<Ws>>(error))
+        .or_else(|| middleware_revert_data::<SignerMiddleware<Provider<Http>, LocalWallet>>(error))
+        .or_else(|| middleware_revert_data::<SignerMiddleware<Provider<Ws>, LocalWallet>>(error))
+}
+
+// Errors from the middleware `M` itself, or from a contract call through it
+fn middleware_revert_data<M: Middleware + 'static>(error: &eyre::Report) -> Option<Bytes> {
+    if let Some(middleware_error) = error.downcast_ref::<M::Error>() {
+        return MiddlewareError::as_error_response(middleware_error)?.as_revert_data();
+    }
+    let contract_error = error.downcast_ref::<ContractError<M>>()?;
+    contract_error.as_revert().cloned().or_else(|| {
+        MiddlewareError::as_error_response(contract_error.as_middleware_error()?)?.as_revert_data()
+    })
+}
+
+// Decodes revert data into something readable. Checks for the builtin `Error(string)` and
+// `Panic(uint256)` first, then any custom `error` declared in the given ABIs (in order).
+pub fn decode_revert_data(revert_data: &[u8], abis: &[Value]) -> String {
+    if revert_data.is_empty() {
+        return "reverted without data".to_string();
+    }
+    if revert_data.len() < 4 {
+        return format!("reverted with invalid data 0x{}", hex::encode(revert_data));
+    }
+
+    let (selector, data) = revert_data.split_at(4);
+
+    // Error(string)
+    if selector == [0x08, 0xc3, 0x79, 0xa0] {
+        if let Ok(tokens) = ethers::abi::decode(&[ParamType::String], data) {
+            return format!("Error(reason: {:?})", format_token(&tokens[0]));
+        }
+    }
+
+    // Panic(uint256)
+    if selector == [0x4e, 0x48, 0x7b, 0x71] {
+        if let Ok(tokens) = ethers::abi::decode(&[ParamType::Uint(256)], data) {
+            let code = tokens[0].clone().into_uint().unwrap_or_default();
+            return format!("Panic(code: {:#x}) - {}", code, panic_code_reason(code));
+        }
+    }
+
+    for json_abi in abis {
+        let abi: Abi = match serde_json::from_value(json_abi.clone()) {
+            Ok(abi) => abi,
+            Err(_) => continue,
+        };
+        for error in abi.errors() {
+            if error.signature()[..4] != *selector {
+                continue;
+            }
+            if let Ok(tokens) = error.decode(data) {
+                let args = error
+                    .inputs
+                    .iter()
+                    .zip(tokens.iter())
+                    .enumerate()
+                    .map(|(idx, (param, token))| match param.name.is_empty() {
+                        true => format!("val_{}: {}", idx, format_token(token)),
+                        false => format!("{}: {}", param.name, format_token(token)),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                return format!("{}({})", error.name, args);
+            }
+        }
+    }
+
+    format!(
+        "unknown error 0x{} (data: 0x{})",
+        hex::encode(selector),
+        hex::encode(data)
+    )
+}
+
+// https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+fn panic_code_reason(code: U256) -> &'static str {
+    match code.low_u64() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic underflow or overflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop() on an empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "called a zero-initialized internal function",
+        _ => "unknown panic code",
+    }
+}
+
+// Decodes a log against the events in the given ABIs (in order), e.g.
+// `Transfer(from: 0x.., to: 0x.., value: 0x..)`. None if nothing matches.
+pub fn decode_log(log: &Log, abis: &[Value]) -> Option<String> {
+    let topic0 = log.topics.first()?;
+
+    for json_abi in abis {
+        let abi: Abi = match serde_json::from_value(json_abi.clone()) {
+            Ok(abi) => abi,
+            Err(_) => continue,
+        };
+        for event in abi.events() {
+            if event.anonymous || event.signature() != *topic0 {
+                continue;
+            }
+            let raw_log = RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            };
+            // Same signature can still fail if the indexed params differ (e.g. ERC20 vs
+            // ERC721 Transfer), so keep looking
+            if let Ok(parsed) = event.parse_log(raw_log) {
+                let args = parsed
+                    .params
+                    .iter()
+                    .map(|param| format!("{}: {}", param.name, format_token(&param.value)))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                return Some(format!("{}({})", event.name, args));
+            }
+        }
+    }
+
+    None
+}
+
+// Canonical `name(type1,type2)` signature for a json abi function (or event/error) entry
+pub fn function_signature(abi_item: &Value) -> String {
+    let func_name = abi_item["name"].as_str().unwrap_or_default();
+    let param_types = abi_item["inputs"]
+        .as_array()
+        .map(|inputs| {
+            inputs
+                .iter()
+                .map(param_type_string)
+                .collect::<Vec<String>>()
+                .join(",")
+        })
+        .unwrap_or_default();
+
+    format!("{}({})", func_name, param_types)
+}
+
+// 0x-prefixed 4 byte selector for a json abi function entry
+pub fn function_selector(abi_item: &Value) -> String {
+    let hash = ethers::utils::keccak256(function_signature(abi_item));
+    format!("0x{}", hex::encode(&hash[..4]))
+}
+
+// The json abi function entry matching a selector, if any
+pub fn function_item_by_selector<'a>(abi: &'a Value, selector: &str) -> Option<&'a Value> {
+    abi.as_array()?
+        .iter()
+        .find(|abi_item| abi_item["type"] == "function" && function_selector(abi_item) == selector)
+}
+
+// Human readable `name(param: input, ...)` for a call, using the inputs as the user typed them
+pub fn describe_fn_call(abi_item: &Value, inputs: &HashMap<String, String>) -> String {
+    let func_name = abi_item["name"].as_str().unwrap_or_default();
+    let args = abi_item["inputs"]
+        .as_array()
+        .map(|params| {
+            params
+                .iter()
+                .map(|param| {
+                    let param_name = param["name"].as_str().unwrap_or_default();
+                    let input = inputs.get(param_name).map(String::as_str).unwrap_or("");
+                    format!("{}: {}", param_name, input)
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+        .unwrap_or_default();
+
+    format!("{}({})", func_name, args)
+}
+
+pub fn selector_to_bytes(selector: &str) -> Result<Selector> {
+    let bytes = hex::decode(selector.strip_prefix("0x").unwrap_or(selector))?;
+    bytes
+        .try_into()
+        .map_err(|_| eyre!("Selector {:?} is not 4 bytes", selector))
+}
+
+fn get_function_by_selector<'a>(abi: &'a Abi, selector: &str) -> Result<&'a Function> {
+    let selector_bytes = selector_to_bytes(selector)?;
+    abi.functions()
+        .find(|func| func.short_signature() == selector_bytes)
+        .ok_or_else(|| eyre!("No function with selector {} in ABI", selector))
+}
+
 pub fn parse_input_to_token(param_kind: ParamType, input_value: String) -> Result<Token> {
     match param_kind {
         ParamType::Address => input_value
@@ -141,59 +313,553 @@ pub fn parse_input_to_token(param_kind: ParamType, input_value: String) -> Resul
             .map(Token::Bool)
             .map_err(|_| eyre!("Failed to parse Bool")),
         ParamType::String => Ok(Token::String(input_value.clone())),
-        ParamType::Uint(_) => {
-            let value = if input_value.starts_with("0x") {
-                BigUint::from_str_radix(&input_value[2..], 16)?
-            } else {
-                BigUint::from_str_radix(&input_value, 10)?
+        ParamType::Uint(bits) => {
+            let value = parse_number_input(&input_value)?;
+            let max = (BigInt::from(1) << bits) - 1;
+            if value.sign() == Sign::Minus || value > max {
+                return Err(eyre!(
+                    "{} is out of range for uint{} (0 to {})",
+                    value,
+                    bits,
+                    max
+                ));
+            }
+            Ok(Token::Uint(bigint_to_u256(&value)))
+        }
+        ParamType::Int(bits) => {
+            let value = parse_number_input(&input_value)?;
+            let max = (BigInt::from(1) << (bits - 1)) - 1;
+            let min = -(BigInt::from(1) << (bits - 1));
+            if value < min || value > max {
+                return Err(eyre!(
+                    "{} is out of range for int{} ({} to {})",
+                    value,
+                    bits,
+                    min,
+                    max
+                ));
+            }
+            // Negative values are always sign-extended to the full 256 bit word
+            let encoded = match value.sign() {
+                Sign::Minus => (BigInt::from(1) << 256) + value,
+                _ => value,
             };
-            let bytes = value.to_bytes_be();
-            let u256_value = U256::from_big_endian(&bytes);
-            Ok(Token::Uint(u256_value))
-        }
-
-        // this def isn't quite right, needs some work
-        ParamType::Int(_) => {
-            if input_value.starts_with('-') {
-                // Handle negative numbers
-                let positive_part = &input_value[1..]; // Strip the `-`
-                let value = if positive_part.starts_with("0x") {
-                    BigInt::from_str_radix(&positive_part[2..], 16)? // strip the 0x
-                } else {
-                    BigInt::from_str_radix(positive_part, 10)?
-                };
-                // Calculate two's complement
-                let max_u256 = BigInt::from(2).pow(256);
-                let two_complement_value = max_u256 - value;
-                let (_, bytes) = two_complement_value.to_bytes_be();
-                let u256_value = U256::from_big_endian(&bytes);
-                Ok(Token::Int(u256_value))
-            } else {
-                // Handle positive numbers as before
-                let value = if input_value.starts_with("0x") {
-                    BigUint::from_str_radix(&input_value[2..], 16)?
-                } else {
-                    BigUint::from_str_radix(&input_value, 10)?
+            Ok(Token::Int(bigint_to_u256(&encoded)))
+        }
+        ParamType::Bytes => parse_bytes_input(&input_value).map(Token::Bytes),
+        ParamType::FixedBytes(size) => {
+            let mut bytes = parse_bytes_input(&input_value)?;
+            if bytes.len() > size {
+                return Err(eyre!(
+                    "Value is {} bytes, too long for bytes{}",
+                    bytes.len(),
+                    size
+                ));
+            }
+            // bytesN values are right-padded, e.g. 0xdead as bytes4 -> 0xdead0000
+            bytes.resize(size, 0);
+            Ok(Token::FixedBytes(bytes))
+        }
+
+        ParamType::Array(inner_kind) => {
+            let elements = split_composite_input(&input_value)?;
+            let tokens = elements
+                .into_iter()
+                .enumerate()
+                .map(|(idx, element)| parse_element_to_token(*inner_kind.clone(), element, idx))
+                .collect::<Result<Vec<Token>>>()?;
+            Ok(Token::Array(tokens))
+        }
+        ParamType::FixedArray(inner_kind, len) => {
+            let elements = split_composite_input(&input_value)?;
+            if elements.len() != len {
+                return Err(eyre!("Expected {} elements, got {}", len, elements.len()));
+            }
+            let tokens = elements
+                .into_iter()
+                .enumerate()
+                .map(|(idx, element)| parse_element_to_token(*inner_kind.clone(), element, idx))
+                .collect::<Result<Vec<Token>>>()?;
+            Ok(Token::FixedArray(tokens))
+        }
+        ParamType::Tuple(member_kinds) => {
+            let elements = split_composite_input(&input_value)?;
+            if elements.len() != member_kinds.len() {
+                return Err(eyre!(
+                    "Expected {} tuple members, got {}",
+                    member_kinds.len(),
+                    elements.len()
+                ));
+            }
+            let tokens = member_kinds
+                .into_iter()
+                .zip(elements)
+                .enumerate()
+                .map(|(idx, (kind, element))| parse_element_to_token(kind, element, idx))
+                .collect::<Result<Vec<Token>>>()?;
+            Ok(Token::Tuple(tokens))
+        }
+    }
+}
+
+// Parses a (possibly negative) integer literal. Accepts hex (`0xff`), decimals with an
+// exponent (`1e18`, `2.5e6`) and an optional unit suffix (`1.5 ether`, `20 gwei`, `100 wei`).
+// The result must be a whole number once the exponent/unit are applied.
+pub fn parse_number_input(input_value: &str) -> Result<BigInt> {
+    let cleaned = input_value.trim().replace('_', "").to_lowercase();
+
+    // Check "gwei" before "wei" since it's a suffix of it
+    let (number, unit_decimals) = [("ether", 18), ("eth", 18), ("gwei", 9), ("wei", 0)]
+        .iter()
+        .find_map(|(unit, decimals)| {
+            cleaned
+                .strip_suffix(unit)
+                .map(|number| (number.trim_end().to_string(), *decimals))
+        })
+        .unwrap_or((cleaned.clone(), 0));
+
+    let (is_negative, number) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, number.as_str()),
+    };
+
+    let magnitude = match number.strip_prefix("0x") {
+        Some(hex_str) => {
+            BigInt::from_str_radix(hex_str, 16) // 0x0f -> 15
+                .map_err(|_| eyre!("Failed to parse number {:?}", input_value))?
+                * BigInt::from(10).pow(unit_decimals)
+        }
+        None => parse_decimal_literal(number, unit_decimals)?,
+    };
+
+    Ok(match is_negative {
+        true => -magnitude,
+        false => magnitude,
+    })
+}
+
+// Splits `1.5e3` into its digits and scale and multiplies out the exponent + unit decimals
+fn parse_decimal_literal(number: &str, unit_decimals: u32) -> Result<BigInt> {
+    let malformed = || eyre!("Failed to parse number {:?}", number);
+
+    let (mantissa, exponent) = match number.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().map_err(|_| malformed())?),
+        None => (number, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !all_digits(int_part)
+        || !all_digits(frac_part)
+    {
+        return Err(malformed());
+    }
+
+    // 2^256 has 78 digits, so anything scaled further can't fit a word (and a huge `pow`
+    // would hang the UI, e.g. `1e4000000000`)
+    let max_exponent = 77 + unit_decimals as u64;
+    if exponent.unsigned_abs() > max_exponent {
+        return Err(eyre!(
+            "Exponent {} out of range (max {})",
+            exponent,
+            max_exponent
+        ));
+    }
+
+    let digits = BigInt::from_str_radix(&format!("0{}{}", int_part, frac_part), 10)?;
+    let scale = exponent + unit_decimals as i64 - frac_part.len() as i64;
+
+    if scale >= 0 {
+        return Ok(digits * BigInt::from(10).pow(scale as u32));
+    }
+
+    let divisor = BigInt::from(10).pow(scale.unsigned_abs() as u32);
+    if (&digits % &divisor).sign() != Sign::NoSign {
+        return Err(eyre!("{} is not a whole number", number));
+    }
+    Ok(digits / divisor)
+}
+
+fn bigint_to_u256(value: &BigInt) -> U256 {
+    let (_, bytes) = value.to_bytes_be();
+    U256::from_big_endian(&bytes)
+}
+
+// Formats a decoded token for display. Mostly defers to ethers' Display impl, but signed
+// ints are shown as (possibly negative) decimals rather than raw two's complement hex and
+// addresses/uints/bytes get a 0x prefix.
+pub fn format_token(token: &Token) -> String {
+    match token {
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Uint(value) => format!("{:#x}", value),
+        Token::Address(address) => format!("{:#x}", address),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Array(tokens) | Token::FixedArray(tokens) => format!(
+            "[{}]",
+            tokens
+                .iter()
+                .map(format_token)
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        Token::Tuple(tokens) => format!(
+            "({})",
+            tokens
+                .iter()
+                .map(format_token)
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        _ => format!("{}", token),
+    }
+}
+
+// Bytes inputs are expected as 0x-prefixed hex. Wrapping the value in double quotes
+// (e.g. `"hello"`) opts into encoding the text itself as UTF-8 instead.
+pub fn parse_bytes_input(input_value: &str) -> Result<Vec<u8>> {
+    let trimmed = input_value.trim();
+
+    if let Some(text) = unquote(trimmed) {
+        return Ok(text.into_bytes());
+    }
+
+    match trimmed.strip_prefix("0x") {
+        Some(hex_str) if hex_str.len() % 2 == 1 => {
+            Err(eyre!("Odd number of hex digits in {:?}", trimmed))
+        }
+        Some(hex_str) => {
+            hex::decode(hex_str).map_err(|e| eyre!("Failed to parse bytes {:?}: {}", trimmed, e))
+        }
+        None => Err(eyre!(
+            "Bytes must be 0x-prefixed hex; wrap in quotes to encode {:?} as UTF-8",
+            trimmed
+        )),
+    }
+}
+
+// Parses a single element of an array/tuple literal, prefixing any error with its index so
+// nested failures read like `[1][0]: Failed to parse Address`
+fn parse_element_to_token(param_kind: ParamType, element: String, idx: usize) -> Result<Token> {
+    // Strings inside a composite can be quoted so they may contain commas or brackets
+    let element = match param_kind {
+        ParamType::String => unquote(&element).unwrap_or(element),
+        _ => element,
+    };
+
+    parse_input_to_token(param_kind, element).map_err(|e| {
+        let msg = e.to_string();
+        match msg.starts_with('[') {
+            true => eyre!("[{}]{}", idx, msg),
+            false => eyre!("[{}]: {}", idx, msg),
+        }
+    })
+}
+
+// Splits a JSON-style `[a, b, [c, d]]` or Solidity-style `(a, b, (c, d))` literal into its
+// top-level elements. Brackets/parens nested inside the literal and anything in double quotes
+// are left untouched for the recursive call to handle.
+fn split_composite_input(input_value: &str) -> Result<Vec<String>> {
+    let trimmed = input_value.trim();
+    let inner = match (trimmed.chars().next(), trimmed.chars().last()) {
+        (Some('['), Some(']')) | (Some('('), Some(')')) if trimmed.len() >= 2 => {
+            &trimmed[1..trimmed.len() - 1]
+        }
+        _ => {
+            return Err(eyre!(
+                "Expected a `[...]` or `(...)` literal, got {:?}",
+                trimmed
+            ))
+        }
+    };
+
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut elements: Vec<String> = Vec::new();
+    let mut current = String::new();
+    // Open brackets still waiting on their closer, so `[1,2)` is caught as a mismatch
+    let mut open_brackets: Vec<char> = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for c in inner.chars() {
+        if in_quotes {
+            current.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_quotes = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_quotes = true;
+                current.push(c);
+            }
+            '[' | '(' => {
+                open_brackets.push(c);
+                current.push(c);
+            }
+            ']' | ')' => {
+                let opener = match c {
+                    ']' => '[',
+                    _ => '(',
                 };
-                let bytes = value.to_bytes_be();
-                let u256_value = U256::from_big_endian(&bytes);
-                Ok(Token::Int(u256_value))
+                if open_brackets.pop() != Some(opener) {
+                    return Err(eyre!("Mismatched brackets in {:?}", trimmed));
+                }
+                current.push(c);
             }
+            ',' if open_brackets.is_empty() => {
+                elements.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(eyre!("Unterminated quote in {:?}", trimmed));
+    }
+    if !open_brackets.is_empty() {
+        return Err(eyre!("Unbalanced brackets in {:?}", trimmed));
+    }
+    elements.push(current.trim().to_string());
+
+    if let Some(idx) = elements.iter().position(|e| e.is_empty()) {
+        return Err(eyre!("[{}]: Empty element", idx));
+    }
+
+    Ok(elements)
+}
+
+// Strips one pair of surrounding double quotes, if present, and unescapes `\"` and `\\`
+// inside them. None if the closing quote is itself escaped (e.g. `"abc\"`).
+fn unquote(value: &str) -> Option<String> {
+    let inner = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                escaped @ ('"' | '\\') => text.push(escaped),
+                other => {
+                    text.push('\\');
+                    text.push(other);
+                }
+            },
+            _ => text.push(c),
         }
-        ParamType::Bytes => Ok(Token::Bytes(input_value.into_bytes())),
-        ParamType::FixedBytes(_) => Ok(Token::FixedBytes(input_value.into_bytes())),
-
-        // TODO:
-        // ParamType::Array(_) => Ok(Token::Array(
-        //     input_value.clone().into_bytes().into_iter().collect(),
-        // )),
-        // ParamType::FixedArray(_, _) => {
-        //     Some(Token::FixedArray(input_value.clone().into_bytes().into_iter().collect()))
-        // }
-        // ParamType::Tuple(_) => {
-        //     Some(Token::Tuple(input_value.clone().into_bytes().into_iter().collect()))
-        // }
-        // ... handle other cases similarly ...
-        _ => Err(eyre!("Unsupported or unhandled type")),
+    }
+    Some(text)
+}
+
+// Expands `tuple` types in a json abi param into their component types so the input hint
+// shows e.g. `(uint256,address)[]` instead of `tuple[]`
+pub fn param_type_string(param: &Value) -> String {
+    let param_type = param["type"].as_str().unwrap_or("Unknown type");
+    match param_type.strip_prefix("tuple") {
+        Some(array_suffix) => {
+            let components = param["components"]
+                .as_array()
+                .map(|components| {
+                    components
+                        .iter()
+                        .map(param_type_string)
+                        .collect::<Vec<String>>()
+                        .join(",")
+                })
+                .unwrap_or_default();
+            format!("({}){}", components, array_suffix)
+        }
+        None => param_type.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{
+        middleware::signer::SignerMiddlewareError,
+        providers::{JsonRpcError, ProviderError},
+    };
+
+    #[test]
+    fn split_composite_input_nested() {
+        assert_eq!(
+            split_composite_input("[1, [2, 3], (4, \"a,b\")]").unwrap(),
+            vec!["1", "[2, 3]", "(4, \"a,b\")"]
+        );
+        assert!(split_composite_input("[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn split_composite_input_rejects_mismatched_brackets() {
+        assert!(split_composite_input("[1,2)").is_err());
+        assert!(split_composite_input("[[1,2),3]").is_err());
+        assert!(split_composite_input("[(1,2],3]").is_err());
+        assert!(split_composite_input("[[1,2]").is_err());
+        assert!(split_composite_input("[1,,2]").is_err());
+        assert!(split_composite_input("[\"a]").is_err());
+    }
+
+    #[test]
+    fn split_composite_input_escaped_quotes() {
+        assert_eq!(
+            split_composite_input(r#"["a\",b", "c"]"#).unwrap(),
+            vec![r#""a\",b""#, r#""c""#]
+        );
+    }
+
+    #[test]
+    fn unquote_unescapes() {
+        assert_eq!(unquote(r#""say \"hi\"""#).as_deref(), Some(r#"say "hi""#));
+        assert_eq!(unquote(r#""a\\b""#).as_deref(), Some(r"a\b"));
+        assert_eq!(unquote(r#""C:\path""#).as_deref(), Some(r"C:\path"));
+        assert_eq!(unquote(r#""abc\""#), None);
+        assert_eq!(unquote("abc"), None);
+    }
+
+    #[test]
+    fn parse_string_array_with_escapes() {
+        let token = parse_input_to_token(
+            ParamType::Array(Box::new(ParamType::String)),
+            r#"["a\"b", "c,d"]"#.to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            token,
+            Token::Array(vec![
+                Token::String("a\"b".to_string()),
+                Token::String("c,d".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_number_input_literals() {
+        let parse = |s: &str| parse_number_input(s).unwrap().to_string();
+        assert_eq!(parse("1e18"), "1000000000000000000");
+        assert_eq!(parse("2.5e6"), "2500000");
+        assert_eq!(parse("1.5 ether"), "1500000000000000000");
+        assert_eq!(parse("20 gwei"), "20000000000");
+        assert_eq!(parse("100 wei"), "100");
+        assert_eq!(parse("0x0f"), "15");
+        assert_eq!(parse("-1_000"), "-1000");
+        assert_eq!(parse("15e-1 gwei"), "1500000000");
+    }
+
+    #[test]
+    fn parse_number_input_rejects_bad_literals() {
+        assert!(parse_number_input("1.5").is_err());
+        assert!(parse_number_input("1e-1").is_err());
+        assert!(parse_number_input("abc").is_err());
+        assert!(parse_number_input(".").is_err());
+        assert!(parse_number_input("1e4000000000").is_err());
+        assert!(parse_number_input("1e-4000000000").is_err());
+        assert!(parse_number_input("1e-9223372036854775808").is_err());
+        assert!(parse_number_input("1e9223372036854775807 ether").is_err());
+        assert!(parse_number_input("1e95 ether").is_ok());
+        assert!(parse_number_input("1e96 ether").is_err());
+    }
+
+    #[test]
+    fn parse_uint_range_checked() {
+        assert!(parse_input_to_token(ParamType::Uint(8), "255".to_string()).is_ok());
+        assert!(parse_input_to_token(ParamType::Uint(8), "256".to_string()).is_err());
+        assert!(parse_input_to_token(ParamType::Uint(8), "-1".to_string()).is_err());
+        assert_eq!(
+            parse_input_to_token(ParamType::Int(8), "-1".to_string()).unwrap(),
+            Token::Int(U256::MAX)
+        );
+        assert!(parse_input_to_token(ParamType::Int(8), "-129".to_string()).is_err());
+    }
+
+    #[test]
+    fn parse_tuple_and_fixed_array() {
+        let token = parse_input_to_token(
+            ParamType::Tuple(vec![
+                ParamType::Bool,
+                ParamType::FixedArray(Box::new(ParamType::Uint(8)), 2),
+            ]),
+            "(true, [1, 2])".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            token,
+            Token::Tuple(vec![
+                Token::Bool(true),
+                Token::FixedArray(vec![Token::Uint(1.into()), Token::Uint(2.into())])
+            ])
+        );
+
+        let err = parse_input_to_token(
+            ParamType::FixedArray(Box::new(ParamType::Uint(8)), 2),
+            "[1, 2, 3]".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Expected 2 elements, got 3");
+    }
+
+    #[test]
+    fn revert_data_from_errors() {
+        let revert = || JsonRpcError {
+            code: 3,
+            message: "execution reverted".to_string(),
+            data: Some(
+                "0x4e487b710000000000000000000000000000000000000000000000000000000000000001".into(),
+            ),
+        };
+        let expected = Some(Bytes::from(
+            hex::decode("4e487b710000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap(),
+        ));
+
+        let ws_error = WsClientError::JsonRpcError(revert());
+        assert_eq!(
+            revert_data_from_error(&eyre::Report::new(ws_error)),
+            expected
+        );
+
+        let provider_error =
+            || ProviderError::JsonRpcClientError(Box::new(WsClientError::JsonRpcError(revert())));
+        assert_eq!(
+            revert_data_from_error(&eyre::Report::new(provider_error())),
+            expected
+        );
+
+        let signer_error: SignerMiddlewareError<Provider<Ws>, LocalWallet> =
+            SignerMiddlewareError::MiddlewareError(provider_error());
+        assert_eq!(
+            revert_data_from_error(&eyre::Report::new(signer_error)),
+            expected
+        );
+
+        let contract_error: ContractError<SignerMiddleware<Provider<Http>, LocalWallet>> =
+            ContractError::Revert(expected.clone().unwrap());
+        assert_eq!(
+            revert_data_from_error(&eyre::Report::new(contract_error)),
+            expected
+        );
+
+        let contract_error: ContractError<Provider<Ws>> = ContractError::MiddlewareError {
+            e: provider_error(),
+        };
+        assert_eq!(
+            revert_data_from_error(&eyre::Report::new(contract_error)),
+            expected
+        );
+
+        let not_a_revert = ProviderError::CustomError("connection refused".to_string());
+        assert_eq!(
+            revert_data_from_error(&eyre::Report::new(not_a_revert)),
+            None
+        );
     }
 }
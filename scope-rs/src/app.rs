@@ -1,19 +1,35 @@
 use crate::{
     backend,
-    components::{ContractSelectorSection, DeployedSection, HeaderSection, TxConfigSection},
+    components::{
+        ChainSection, ContractSelectorSection, DecoderSection, DeployedSection, HeaderSection,
+        HistorySection, NetworkSection, SignerSection, SnapshotSection, TxConfigSection,
+        WatchSection,
+    },
     shared_state::STATE,
     utils,
 };
 use egui::containers::Frame;
 
+// Storage key for the tx history, which lives in shared state rather than on the Frontend
+const TX_HISTORY_KEY: &str = "tx_history";
+const NETWORK_PROFILES_KEY: &str = "network_profiles";
+const ACTIVE_NETWORK_KEY: &str = "active_network";
+
 // Serde stuff for saving state, TODO
 #[derive(serde::Deserialize, serde::Serialize, Default)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct Frontend {
     header_section: HeaderSection,
+    network_section: NetworkSection,
     contract_selector_section: ContractSelectorSection,
     tx_config_section: TxConfigSection,
+    signer_section: SignerSection,
     deploy_section: DeployedSection,
+    watch_section: WatchSection,
+    chain_section: ChainSection,
+    snapshot_section: SnapshotSection,
+    decoder_section: DecoderSection,
+    history_section: HistorySection,
 
     render_configs: RenderConfigs,
 
@@ -26,18 +42,37 @@ pub struct Frontend {
 pub struct RenderConfigs {
     pub verbosity: i32,
     pub load_address: String,
+    pub etch_address: String,
     pub selected_name: Option<String>,
     pub show_new_address_input: bool,
     pub show_balance_input: bool,
+    pub show_nonce_input: bool,
 }
 
 impl Frontend {
     /// Called once before the first frame.
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        backend::initialize();
         let mut panel_frame = Frame::default();
         utils::get_and_set_theme(cc, &mut panel_frame);
 
+        // Restore the tx history and networks from the last session
+        if let Some(storage) = cc.storage {
+            if let Some(tx_history) = eframe::get_value(storage, TX_HISTORY_KEY) {
+                *STATE.tx_history.write().unwrap() = tx_history;
+            }
+            if let Some(network_profiles) = eframe::get_value(storage, NETWORK_PROFILES_KEY) {
+                *STATE.network_profiles.write().unwrap() = network_profiles;
+            }
+            if let Some(active_network) = eframe::get_value(storage, ACTIVE_NETWORK_KEY) {
+                *STATE.active_network.write().unwrap() = active_network;
+            }
+        }
+
+        *STATE.egui_ctx.write().unwrap() = Some(cc.egui_ctx.clone());
+
+        // Connects to the restored network
+        backend::initialize();
+
         Self {
             render_configs: RenderConfigs {
                 verbosity: 2,
@@ -50,6 +85,21 @@ impl Frontend {
 }
 
 impl eframe::App for Frontend {
+    /// Called by the framework to save state before shutdown (and periodically).
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, TX_HISTORY_KEY, &*STATE.tx_history.read().unwrap());
+        eframe::set_value(
+            storage,
+            NETWORK_PROFILES_KEY,
+            &*STATE.network_profiles.read().unwrap(),
+        );
+        eframe::set_value(
+            storage,
+            ACTIVE_NETWORK_KEY,
+            &*STATE.active_network.read().unwrap(),
+        );
+    }
+
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Main panel
@@ -64,27 +114,36 @@ impl eframe::App for Frontend {
                 // Wrap the whole thing in a scroll area
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     self.header_section.show(ui);
+                    self.network_section.show(ui);
                     self.contract_selector_section
                         .show(ui, &mut self.render_configs);
 
                     ui.separator();
 
                     self.tx_config_section.show(ui, &mut self.render_configs);
+                    self.signer_section.show(ui);
 
                     ui.separator();
 
                     // create collapsable headers for each address
                     self.deploy_section.show(ui, &mut self.render_configs);
+
+                    self.watch_section.show(ui);
+                    self.chain_section.show(ui);
+                    self.snapshot_section.show(ui);
+                    self.decoder_section.show(ui);
+                    self.history_section.show(ui);
                 });
             });
 
         // Throttle refresh based on focus
         // - First case forces renders while mouse is hovering outside of extension
         //   (but focus still inside)
-        // - Second case forces occasional renders when kb/mouse focus is outside extension (3sec)
+        // - Second case forces occasional renders when kb/mouse focus is outside extension (3sec);
+        //   not needed while live, since new blocks trigger a repaint themselves
         if *STATE.has_focus.read().unwrap() {
             ctx.request_repaint_after(std::time::Duration::from_millis(200));
-        } else {
+        } else if !*STATE.live_updates.read().unwrap() {
             ctx.request_repaint_after(std::time::Duration::from_millis(3000));
         }
     }
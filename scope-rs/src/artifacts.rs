@@ -0,0 +1,394 @@
+use crate::bytecode::{self, BytecodeMatch};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+// Index of every artifact in the project's output dir(s). Built from the artifact paths plus
+// the `source path -> contract names` listing of each build-info file, both of which the
+// extension sends over (see `getArtifactFiles` in helpers.ts).
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct ArtifactIndex {
+    pub entries: Vec<ArtifactEntry>,
+    pub storage_layouts: HashMap<String, Value>, // `source:Contract` -> solc storageLayout
+    pub codes: Vec<ArtifactCode>, // every artifact's ABI + runtime code, see backend::load_artifact_codes
+    pub codes_requested: bool,
+    pub codes_loaded: bool,
+}
+
+// What's needed to recognize an artifact's deployed code, and use it once recognized
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactCode {
+    pub artifact_path: String,
+    pub deployed_bytecode: String, // empty for interfaces and abstract contracts
+    pub masked: Vec<(usize, usize)>, // (start, length) of immutables and library links
+    pub abi: Value,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactEntry {
+    pub contract_name: String,
+    pub source_path: String, // e.g. src/nested/Token.sol; just the file name if no build-info matched
+    pub artifact_path: String, // uri of the artifact .json, what we ask the extension to load
+}
+
+impl ArtifactEntry {
+    // What's shown in the contract selector, e.g. src/nested/Token.sol:Token
+    pub fn display_name(&self) -> String {
+        format!("{}:{}", self.source_path, self.contract_name)
+    }
+
+    pub fn is_test(&self) -> bool {
+        self.source_path.ends_with(".t.sol")
+    }
+
+    // Open files are workspace relative; unmatched entries only know their file name
+    pub fn is_open(&self, open_files: &[String]) -> bool {
+        open_files.iter().any(|open_file| {
+            *open_file == self.source_path || open_file.ends_with(&format!("/{}", self.source_path))
+        })
+    }
+}
+
+impl ArtifactIndex {
+    pub fn build(
+        artifact_paths: Vec<String>,
+        build_infos: Vec<String>,
+        storage_layouts: Vec<String>,
+    ) -> Self {
+        // (source path, contract name) for everything in the build-info files; the same
+        // source shows up in each build-info it was compiled in, so dedupe
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut sources: Vec<(String, String)> = Vec::new();
+        for build_info in build_infos.iter() {
+            let contracts: HashMap<String, Vec<String>> = match serde_json::from_str(build_info) {
+                Ok(contracts) => contracts,
+                Err(e) => {
+                    log!("skipping malformed build-info: {}", e);
+                    continue;
+                }
+            };
+            for (source_path, contract_names) in contracts {
+                for contract_name in contract_names {
+                    let source = (source_path.clone(), contract_name);
+                    if seen.insert(source.clone()) {
+                        sources.push(source);
+                    }
+                }
+            }
+        }
+
+        // Artifacts live at `<out>/<File.sol>/<Contract>.json`. When two sources share a file
+        // name, foundry nests them under (part of) their source dirs instead, so score each
+        // candidate source by how many trailing path segments it shares with the artifact.
+        // The artifact paths are uris, so decode them first.
+        let decoded_paths: Vec<String> = artifact_paths.iter().map(|p| percent_decode(p)).collect();
+        let artifact_segments: Vec<Vec<&str>> = decoded_paths
+            .iter()
+            .map(|decoded_path| {
+                let mut segments: Vec<&str> = decoded_path.split('/').collect();
+                segments.pop(); // <Contract>.json
+                segments
+            })
+            .collect();
+
+        let mut candidates: Vec<(usize, usize, usize)> = Vec::new(); // (score, artifact, source)
+        for (artifact_idx, decoded_path) in decoded_paths.iter().enumerate() {
+            let contract_name = contract_name_from_path(decoded_path);
+            for (source_idx, (source_path, source_contract)) in sources.iter().enumerate() {
+                if *source_contract != contract_name {
+                    continue;
+                }
+                let source_segments: Vec<&str> = source_path.split('/').collect();
+                let score = common_suffix_len(&artifact_segments[artifact_idx], &source_segments);
+                // Must at least share the file name
+                if score > 0 {
+                    candidates.push((score, artifact_idx, source_idx));
+                }
+            }
+        }
+
+        // Best matches first, and each source can only back one artifact
+        candidates.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        let mut artifact_sources: HashMap<usize, usize> = HashMap::new();
+        let mut used_sources: HashSet<usize> = HashSet::new();
+        for (_, artifact_idx, source_idx) in candidates {
+            if artifact_sources.contains_key(&artifact_idx) || used_sources.contains(&source_idx) {
+                continue;
+            }
+            artifact_sources.insert(artifact_idx, source_idx);
+            used_sources.insert(source_idx);
+        }
+
+        let mut entries: Vec<ArtifactEntry> = artifact_paths
+            .iter()
+            .enumerate()
+            .filter(|(artifact_idx, _)| artifact_segments[*artifact_idx].len() > 1)
+            .map(|(artifact_idx, artifact_path)| {
+                let source_path = match artifact_sources.get(&artifact_idx) {
+                    Some(source_idx) => sources[*source_idx].0.clone(),
+                    None => artifact_segments[artifact_idx]
+                        .last()
+                        .unwrap_or(&"")
+                        .to_string(),
+                };
+                ArtifactEntry {
+                    contract_name: contract_name_from_path(&decoded_paths[artifact_idx]),
+                    source_path,
+                    artifact_path: artifact_path.clone(),
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.display_name());
+
+        // Only there if the project builds with the storageLayout output
+        let storage_layouts = storage_layouts
+            .iter()
+            .filter_map(|layouts| serde_json::from_str::<HashMap<String, Value>>(layouts).ok())
+            .flatten()
+            .collect();
+
+        Self {
+            entries,
+            storage_layouts,
+            ..Default::default()
+        }
+    }
+
+    // The index may have been rebuilt since these were asked for, so only keep the ones for
+    // artifacts that are still around
+    pub fn set_codes(&mut self, artifact_codes: Vec<String>) {
+        self.codes = artifact_codes
+            .iter()
+            .filter_map(|code| match serde_json::from_str::<ArtifactCode>(code) {
+                Ok(code) => Some(code),
+                Err(e) => {
+                    log!("skipping malformed artifact code: {}", e);
+                    None
+                }
+            })
+            .filter(|code| {
+                self.entries
+                    .iter()
+                    .any(|entry| entry.artifact_path == code.artifact_path)
+            })
+            .collect();
+        self.codes_requested = true;
+        self.codes_loaded = true;
+    }
+
+    // Ranked artifacts for some on-chain runtime code, see `bytecode::rank_matches`
+    pub fn match_runtime_code(&self, code: &[u8]) -> Vec<BytecodeMatch> {
+        let artifact_codes = self.codes.iter().filter_map(|artifact_code| {
+            if artifact_code.deployed_bytecode.is_empty() {
+                return None;
+            }
+            let entry = self
+                .entries
+                .iter()
+                .find(|entry| entry.artifact_path == artifact_code.artifact_path)?;
+            Some((
+                artifact_code,
+                entry.display_name(),
+                entry.contract_name.clone(),
+            ))
+        });
+        bytecode::rank_matches(code, artifact_codes)
+    }
+
+    // Case insensitive match against `source:Contract`, optionally only for open files
+    pub fn search(&self, query: &str, open_files: Option<&[String]>) -> Vec<&ArtifactEntry> {
+        let query = query.trim().to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| entry.display_name().to_lowercase().contains(&query))
+            .filter(|entry| open_files.is_none_or(|open_files| entry.is_open(open_files)))
+            .collect()
+    }
+
+    pub fn get_by_display_name(&self, display_name: &str) -> Option<&ArtifactEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.display_name() == display_name)
+    }
+}
+
+fn contract_name_from_path(artifact_path: &str) -> String {
+    artifact_path
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".json")
+        .to_string()
+}
+
+fn common_suffix_len(a: &[&str], b: &[&str]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+// Decodes the %XX escapes in a uri path; anything malformed is left as is
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            if let Some(Ok(byte)) = bytes.get(idx + 1..idx + 3).map(hex::decode) {
+                decoded.extend(byte);
+                idx += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry<'a>(index: &'a ArtifactIndex, artifact_path: &str) -> &'a ArtifactEntry {
+        index
+            .entries
+            .iter()
+            .find(|entry| entry.artifact_path == artifact_path)
+            .unwrap()
+    }
+
+    fn code_json(artifact_path: &str, code: &[u8], masked: Vec<(usize, usize)>) -> String {
+        json!({
+            "artifactPath": artifact_path,
+            "deployedBytecode": format!("0x{}", hex::encode(code)),
+            "masked": masked,
+            "abi": [],
+        })
+        .to_string()
+    }
+
+    // body + `{"solc": 0.8.19}` metadata, so only the tail changes between builds
+    fn runtime(body: &[u8], solc_patch: u8) -> Vec<u8> {
+        let mut code = body.to_vec();
+        code.extend([
+            0xa1, 0x64, 0x73, 0x6f, 0x6c, 0x63, 0x43, 0x00, 0x08, solc_patch,
+        ]);
+        code.extend([0x00, 0x0a]);
+        code
+    }
+
+    #[test]
+    fn build_scores_path_suffixes() {
+        let index = ArtifactIndex::build(
+            vec![
+                "file:///p/out/Token.sol/Token.json".to_string(),
+                "file:///p/out/nested/Token.sol/Token.json".to_string(),
+                "file:///my%20project/out/Lib.sol/Lib.json".to_string(),
+                "file:///p/out/Unlisted.sol/Unlisted.json".to_string(),
+                "Orphan.json".to_string(),
+            ],
+            vec![
+                json!({ "src/Token.sol": ["Token"], "src/Lib.sol": ["Lib"] }).to_string(),
+                json!({ "src/nested/Token.sol": ["Token"], "src/Token.sol": ["Token"] })
+                    .to_string(),
+            ],
+            vec![json!({ "src/Token.sol:Token": { "storage": [] } }).to_string()],
+        );
+
+        // the nested artifact shares two segments with its source, so it gets it first
+        assert_eq!(
+            entry(&index, "file:///p/out/nested/Token.sol/Token.json").source_path,
+            "src/nested/Token.sol"
+        );
+        assert_eq!(
+            entry(&index, "file:///p/out/Token.sol/Token.json").source_path,
+            "src/Token.sol"
+        );
+        // matched on the decoded path, but still loaded by its uri
+        let lib = entry(&index, "file:///my%20project/out/Lib.sol/Lib.json");
+        assert_eq!(lib.display_name(), "src/Lib.sol:Lib");
+        // no build-info lists it, so only the file name is known
+        assert_eq!(
+            entry(&index, "file:///p/out/Unlisted.sol/Unlisted.json").display_name(),
+            "Unlisted.sol:Unlisted"
+        );
+
+        assert_eq!(index.entries.len(), 4);
+        let names: Vec<String> = index.entries.iter().map(|e| e.display_name()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        assert!(index.storage_layouts.contains_key("src/Token.sol:Token"));
+    }
+
+    #[test]
+    fn runtime_code_matches() {
+        let mut index = ArtifactIndex::build(
+            vec![
+                "out/A.sol/Token.json".to_string(),
+                "out/A.sol/Vault.json".to_string(),
+                "out/A.sol/IToken.json".to_string(),
+            ],
+            vec![json!({ "src/A.sol": ["Token", "Vault", "IToken"] }).to_string()],
+            vec![],
+        );
+
+        let token: Vec<u8> = (0..40).collect();
+        let mut vault = token.clone();
+        vault[20..].iter_mut().for_each(|byte| *byte = 0xee);
+        index.set_codes(vec![
+            code_json("out/A.sol/Token.json", &runtime(&token, 19), vec![]),
+            // an immutable at 8..12
+            code_json("out/A.sol/Vault.json", &runtime(&vault, 19), vec![(8, 4)]),
+            json!({ "artifactPath": "out/A.sol/IToken.json", "deployedBytecode": "", "masked": [], "abi": [] })
+                .to_string(),
+            code_json("out/Gone.sol/Gone.json", &runtime(&token, 19), vec![]),
+        ]);
+        assert!(index.codes_loaded);
+        assert_eq!(index.codes.len(), 3);
+
+        // built with another compiler patch, so only the metadata differs
+        let matches = index.match_runtime_code(&runtime(&token, 20));
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].is_exact());
+        assert_eq!(matches[0].display_name, "src/A.sol:Token");
+        assert_eq!(matches[0].contract_name, "Token");
+
+        // the vault with its immutable filled in
+        let mut deployed_vault = vault.clone();
+        deployed_vault[8..12].copy_from_slice(&[0xaa; 4]);
+        let matches = index.match_runtime_code(&runtime(&deployed_vault, 19));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].contract_name, "Vault");
+        assert!(matches[0].is_exact());
+
+        // close to both, closest first
+        let mut neither = token.clone();
+        neither[8..12].copy_from_slice(&[0xaa; 4]);
+        neither[30..].iter_mut().for_each(|byte| *byte = 0xee);
+        let matches = index.match_runtime_code(&runtime(&neither, 19));
+        let names: Vec<&str> = matches.iter().map(|m| m.contract_name.as_str()).collect();
+        assert_eq!(names, vec!["Vault", "Token"]);
+        assert!(matches.iter().all(|m| !m.is_exact()));
+        assert!(matches[0].similarity > matches[1].similarity);
+
+        assert!(index.match_runtime_code(&[0xfe; 40]).is_empty());
+    }
+
+    #[test]
+    fn percent_decoding() {
+        assert_eq!(percent_decode("my%20project/a%2Fb"), "my project/a/b");
+        assert_eq!(percent_decode("trailing%20"), "trailing ");
+        assert_eq!(percent_decode("%E2%82%AC"), "\u{20ac}");
+        // malformed escapes are left as is
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%2"), "%2");
+        assert_eq!(percent_decode("%zz%+1%-1"), "%zz%+1%-1");
+        assert_eq!(percent_decode("%%20"), "% ");
+    }
+}
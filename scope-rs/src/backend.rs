@@ -1,22 +1,31 @@
 use crate::{
-    components::{CompiledContract, DeployedContract},
+    components::{
+        CompiledContract, DecodedLog, DeployedContract, LogPage, TxKind, TxRecord, TxReplay, Watch,
+    },
+    multicall,
     providers::{self},
-    shared_state::{self, STATE},
+    proxy,
+    shared_state::{self, ConnectionStatus, NetworkProfile, Snapshot, TxConfigs, STATE},
     utils, wasm,
 };
 use ethers::{
     contract::{ContractFactory, ContractInstance},
     prelude::{LocalWallet, Provider, SignerMiddleware},
-    providers::{Http, Middleware},
-    types::{BlockNumber, H160, U256},
+    providers::{Http, Middleware, StreamExt, Ws},
+    types::{
+        transaction::eip2718::TypedTransaction, Block, BlockNumber, Bytes,
+        Eip1559TransactionRequest, Filter, Log, TransactionReceipt, H160, H256, U256, U64,
+    },
     utils::parse_ether,
 };
 use eyre::Result;
 use hex;
+use js_sys::Array;
 
 use serde_json::Value;
 
-use std::sync::Arc;
+use std::{future::Future, sync::Arc, task::Poll};
+use wasm_bindgen::JsValue;
 
 // Functions for sending messages/queries to the extension
 // These will generally call a javascript function exposed in the bridge
@@ -34,6 +43,11 @@ pub fn query_for_open_files() -> Result<()> {
     Ok(())
 }
 
+pub fn query_for_artifact_index() {
+    log!("querying for artifact files");
+    wasm::get_artifact_files();
+}
+
 pub fn query_for_file_contents(file_path: String) -> Result<()> {
     log!("querying for file contents");
     wasm::get_file_contents(file_path);
@@ -46,6 +60,92 @@ pub fn query_for_compiled_solidity(file_path: String) -> Result<()> {
     wasm::get_compiled_solidity(file_path);
     Ok(())
 }
+
+// How long to wait on the extension for artifacts loaded on demand (ms)
+const ARTIFACT_TIMEOUT: u32 = 10_000;
+const ARTIFACT_POLL_INTERVAL: u32 = 50;
+
+// The runtime code + ABIs of the indexed artifacts, only asked for once something needs them
+pub fn query_for_artifact_codes() {
+    if STATE.artifact_index.read().unwrap().codes_requested {
+        return;
+    }
+    let artifact_paths: Array = {
+        let mut artifact_index = STATE.artifact_index.write().unwrap();
+        if artifact_index.codes_requested {
+            return;
+        }
+        artifact_index.codes_requested = true;
+        artifact_index
+            .entries
+            .iter()
+            .map(|entry| JsValue::from_str(&entry.artifact_path))
+            .collect()
+    };
+    wasm::get_artifact_codes(&artifact_paths);
+}
+
+async fn load_artifact_codes() {
+    query_for_artifact_codes();
+    let mut waited = 0;
+    while !STATE.artifact_index.read().unwrap().codes_loaded && waited < ARTIFACT_TIMEOUT {
+        let _ = wasm_bindgen_futures::JsFuture::from(wasm::sleep(ARTIFACT_POLL_INTERVAL)).await;
+        waited += ARTIFACT_POLL_INTERVAL;
+    }
+}
+
+// A whole artifact, e.g. for the storage layout of a contract only recognized by its code
+pub async fn load_artifact(artifact_path: String) -> Result<CompiledContract> {
+    let mut waited = 0;
+    loop {
+        if let Some(compiled) = STATE.compiled_contracts.read().unwrap().get(&artifact_path) {
+            return Ok(compiled.clone());
+        }
+        if waited == 0 {
+            wasm::get_artifact(artifact_path.clone());
+        } else if waited >= ARTIFACT_TIMEOUT {
+            return Err(eyre::eyre!("Timed out loading {}", artifact_path));
+        }
+        let _ = wasm_bindgen_futures::JsFuture::from(wasm::sleep(ARTIFACT_POLL_INTERVAL)).await;
+        waited += ARTIFACT_POLL_INTERVAL;
+    }
+}
+
+// Loads an artifact picked from the index, e.g. in a deployed contract's set code or attach
+// ABI picker, into compiled_contracts
+pub fn query_for_artifact(artifact_path: String) {
+    if STATE
+        .compiled_contracts
+        .read()
+        .unwrap()
+        .contains_key(&artifact_path)
+    {
+        return;
+    }
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = load_artifact(artifact_path).await {
+            send_error_popup(format!("ERROR: {}", e));
+        }
+    });
+}
+
+// Loads the artifact picked for code that only partially matched, for the deployed contract
+// at the address to pick up
+pub fn use_bytecode_match(address: H160, artifact_path: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match load_artifact(artifact_path).await {
+            Ok(compiled) => {
+                STATE
+                    .temp_matched_artifacts
+                    .write()
+                    .unwrap()
+                    .insert(format!("{:#x}", address), compiled);
+            }
+            Err(e) => send_error_popup(format!("ERROR: {}", e)),
+        }
+    });
+}
+
 pub fn send_shell_command(command: String) -> Result<()> {
     log!("sending shell command");
     wasm::execute_shell_command(command);
@@ -67,53 +167,427 @@ pub fn send_ok_popup(ok_text: String) {
 
 pub fn initialize() -> Result<()> {
     query_for_open_files();
-    wasm_bindgen_futures::spawn_local(async {
-        // Set up the client
-        let endpoint = STATE.endpoint.read().unwrap().to_string();
-        let client_wrapper = providers::ClientProviderWrapper::new(endpoint)
-            .await
-            .expect("Could not initialize client");
-        *STATE.client.write().unwrap() = Some(client_wrapper.clone());
-
-        // Set up "from" addresses; easier to just set balance to 100e on init?
-        let addresses = vec![
-            "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string(),
-            "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".to_string(),
-            "0xcccccccccccccccccccccccccccccccccccccccc".to_string(),
-            "0xdddddddddddddddddddddddddddddddddddddddd".to_string(),
-            "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_string(),
-            "0xffffffffffffffffffffffffffffffffffffffff".to_string(),
-            "0x0000000000000000000000000000000000000000".to_string(),
-        ];
-
-        // Set balances to 100e
-        let bal: U256 = parse_ether(100u64).expect("Error parsing init amount");
-        for address in &addresses {
-            // log!("Setting balance of {:?} to {:?}", address, bal);
-
-            let setbal_params: Value = serde_json::json!([address, bal]);
-
-            let _ = client_wrapper
-                .client
-                .provider()
-                .request::<Value, Value>("hardhat_setBalance", setbal_params)
-                .await;
+    query_for_artifact_index();
+    if let Some(profile) = shared_state::read_active_network() {
+        connect(profile);
+    }
+    Ok(())
+}
+
+// (Re)builds the client for the profile. Contracts deployed on the previous chain are put
+// aside and come back when we connect to that chain again.
+pub fn connect(profile: NetworkProfile) {
+    *STATE.connection.write().unwrap() = ConnectionStatus::Connecting;
+    *STATE.live_updates.write().unwrap() = false;
+    let connection_id = {
+        let mut connection_id = STATE.connection_id.write().unwrap();
+        *connection_id += 1;
+        *connection_id
+    };
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let ws_url = profile.ws_url.trim().to_string();
+        let res = connect_to(profile, connection_id).await;
+        // Connected somewhere else in the meantime, that one's status (and client) wins
+        if !is_current_connection(connection_id) {
+            return;
         }
+        let status = match res {
+            Ok(chain_id) => {
+                if !ws_url.is_empty() {
+                    watch_new_blocks(ws_url.clone(), connection_id);
+                    watch_logs(ws_url, connection_id);
+                }
+                ConnectionStatus::Connected { chain_id }
+            }
+            Err(e) => ConnectionStatus::Failed(e.to_string()),
+        };
+        *STATE.connection.write().unwrap() = status;
+    });
+}
+
+fn is_current_connection(connection_id: u64) -> bool {
+    *STATE.connection_id.read().unwrap() == connection_id
+}
+
+// Live updates over ws; the http client is still used for everything else. Both
+// subscriptions end once we connect somewhere else.
+fn watch_new_blocks(ws_url: String, connection_id: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            let provider = Provider::<Ws>::connect(&ws_url).await?;
+            let mut blocks = provider.subscribe_blocks().await?;
+            *STATE.live_updates.write().unwrap() = true;
+
+            while let Some(block) = blocks.next().await {
+                if !is_current_connection(connection_id) {
+                    return Ok(());
+                }
+                on_new_block(block);
+            }
+            Err(eyre::eyre!("block subscription closed"))
+        }
+        .await;
 
-        // Write addresses to state.from_addresses
-        *STATE.from_addresses.write().unwrap() =
-            addresses.iter().map(|a| a.parse().unwrap()).collect();
+        if is_current_connection(connection_id) {
+            *STATE.live_updates.write().unwrap() = false;
+            if let Err(e) = res {
+                send_error_popup(format!("ERROR: live updates stopped: {}", e));
+            }
+        }
+    });
+}
 
-        // Allows us to impersonate anyone via autoImpersonate
-        let impersonate_params: Value = serde_json::json!([true]);
+// How often the log subscription checks whether contracts were loaded or removed
+const LOG_FILTER_CHECK_INTERVAL: u32 = 1_000;
+
+// Subscribed only to the loaded contracts' logs (an unfiltered subscription gets every log on
+// the chain), and resubscribed whenever that set of addresses changes
+fn watch_logs(ws_url: String, connection_id: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            let provider = Provider::<Ws>::connect(&ws_url).await?;
+            loop {
+                let addresses = deployed_addresses();
+                let mut logs = match addresses.is_empty() {
+                    true => None,
+                    false => Some(
+                        provider
+                            .subscribe_logs(&Filter::new().address(addresses.clone()))
+                            .await?,
+                    ),
+                };
+
+                loop {
+                    // The next log, or None once it's time to check the addresses again
+                    let mut recheck = std::pin::pin!(wasm_bindgen_futures::JsFuture::from(
+                        wasm::sleep(LOG_FILTER_CHECK_INTERVAL)
+                    ));
+                    let next = std::future::poll_fn(|cx| {
+                        if let Some(Poll::Ready(log)) =
+                            logs.as_mut().map(|logs| logs.poll_next_unpin(cx))
+                        {
+                            return Poll::Ready(Some(log));
+                        }
+                        recheck.as_mut().poll(cx).map(|_| None)
+                    })
+                    .await;
+
+                    if !is_current_connection(connection_id) {
+                        return Ok::<(), eyre::Report>(());
+                    }
+                    match next {
+                        Some(Some(log)) => on_new_log(log),
+                        Some(None) => return Err(eyre::eyre!("log subscription closed")),
+                        None => {}
+                    }
+                    // dropping the stream unsubscribes
+                    if deployed_addresses() != addresses {
+                        break;
+                    }
+                }
+            }
+        }
+        .await;
+
+        if let Err(e) = res {
+            log!("log subscription stopped: {}", e);
+        }
+    });
+}
+
+fn deployed_addresses() -> Vec<H160> {
+    let mut addresses: Vec<H160> = STATE
+        .deployed_contracts
+        .read()
+        .unwrap()
+        .values()
+        .map(|contract| contract.address_h160)
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+fn on_new_block(block: Block<H256>) {
+    *STATE.latest_block.write().unwrap() = Some(block);
+
+    // Re-run the view calls that have outputs showing
+    for contract in STATE.deployed_contracts.read().unwrap().values() {
+        contract.refresh_view_outputs();
+    }
+    refresh_balances();
+    Watch::refresh_all();
+
+    if let Some(ctx) = STATE.egui_ctx.read().unwrap().as_ref() {
+        ctx.request_repaint();
+    }
+}
+
+// Only logs from contracts we have loaded, picked up by their event viewers
+fn on_new_log(log: Log) {
+    let is_deployed = STATE
+        .deployed_contracts
+        .read()
+        .unwrap()
+        .values()
+        .any(|contract| contract.address_h160 == log.address);
+    if !is_deployed {
+        return;
+    }
+
+    let address_key = format!("{:#x}", log.address);
+    let decoded = DecodedLog::new(log, &shared_state::read_known_abis());
+    STATE
+        .temp_live_logs
+        .write()
+        .unwrap()
+        .entry(address_key)
+        .or_default()
+        .push(decoded);
+}
+
+// Selected "from" address plus every deployed contract
+pub fn refresh_balances() {
+    let mut addresses: Vec<H160> = STATE
+        .deployed_contracts
+        .read()
+        .unwrap()
+        .values()
+        .map(|contract| contract.address_h160)
+        .collect();
+    addresses.push(shared_state::read_tx_configs().from_address);
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let Ok(client_wrapper) = shared_state::read_shared_client() else {
+            return;
+        };
+        for address in addresses {
+            if let Ok(balance) = client_wrapper.client.get_balance(address, None).await {
+                STATE.balances.write().unwrap().insert(address, balance);
+            }
+        }
+    });
+}
+
+// Nothing is touched if a newer connect started while this one was waiting on the node
+async fn connect_to(profile: NetworkProfile, connection_id: u64) -> Result<u64> {
+    let client_wrapper = providers::ClientProviderWrapper::new(profile.url.clone())
+        .await
+        .map_err(|e| eyre::eyre!("{}", e))?;
+    if !is_current_connection(connection_id) {
+        return Err(eyre::eyre!("superseded by a newer connection"));
+    }
+    let chain_id = client_wrapper.chain_id.as_u64();
+
+    if !profile.chain_id.trim().is_empty() {
+        let expected: u64 = profile
+            .chain_id
+            .trim()
+            .parse()
+            .map_err(|_| eyre::eyre!("Invalid chain id {:?}", profile.chain_id))?;
+        if expected != chain_id {
+            return Err(eyre::eyre!(
+                "expected chain {}, node is on {}",
+                expected,
+                chain_id
+            ));
+        }
+    }
+
+    let previous_chain_id = shared_state::read_shared_client()
+        .ok()
+        .map(|previous| previous.chain_id.as_u64());
+    if previous_chain_id != Some(chain_id) {
+        let mut deployed_contracts = STATE.deployed_contracts.write().unwrap();
+        let mut other_chain_contracts = STATE.other_chain_contracts.write().unwrap();
+        let previous_contracts = std::mem::take(&mut *deployed_contracts);
+        if let Some(previous_chain_id) = previous_chain_id {
+            other_chain_contracts.insert(previous_chain_id, previous_contracts);
+        }
+        *deployed_contracts = other_chain_contracts.remove(&chain_id).unwrap_or_default();
+    }
+
+    // Snapshots and blocks belong to the node we were connected to
+    STATE.snapshots.write().unwrap().clear();
+    *STATE.latest_block.write().unwrap() = None;
+    *STATE.endpoint.write().unwrap() = profile.url.clone();
+    *STATE.client.write().unwrap() = Some(client_wrapper.clone());
+
+    if let Some(tx_configs) = profile.tx_configs {
+        *STATE.tx_configs.write().unwrap() = tx_configs;
+    }
+    if let Some(signer) = profile.signer {
+        STATE.tx_configs.write().unwrap().from_address = signer;
+    }
+
+    STATE.balances.write().unwrap().clear();
+    setup_accounts(&client_wrapper).await;
+    if !is_current_connection(connection_id) {
+        return Err(eyre::eyre!("superseded by a newer connection"));
+    }
+    refresh_balances();
+    let identity = node_identity(&client_wrapper).await.ok();
+    if !is_current_connection(connection_id) {
+        return Err(eyre::eyre!("superseded by a newer connection"));
+    }
+    *STATE.node_identity.write().unwrap() = identity;
+    Ok(chain_id)
+}
+
+// Funds the default "from" addresses, and (re)impersonates them plus anything that was
+// impersonated on the previous node
+async fn setup_accounts(client_wrapper: &providers::ClientProviderWrapper) {
+    let addresses: Vec<H160> = vec![
+        "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        "0xcccccccccccccccccccccccccccccccccccccccc",
+        "0xdddddddddddddddddddddddddddddddddddddddd",
+        "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+        "0xffffffffffffffffffffffffffffffffffffffff",
+        "0x0000000000000000000000000000000000000000",
+    ]
+    .iter()
+    .map(|a| a.parse().unwrap())
+    .collect();
+
+    // Set balances to 100e
+    let bal: U256 = parse_ether(100u64).expect("Error parsing init amount");
+    for address in &addresses {
+        let setbal_params: Value = serde_json::json!([address, bal]);
 
         let _ = client_wrapper
             .client
             .provider()
-            .request::<Value, Value>("hardhat_autoImpersonateAccount", impersonate_params)
+            .request::<Value, Value>("hardhat_setBalance", setbal_params)
             .await;
+    }
+
+    // Keep any addresses added by hand or imported
+    let mut to_impersonate = std::mem::take(&mut *STATE.impersonated.write().unwrap());
+    {
+        let mut from_addresses = STATE.from_addresses.write().unwrap();
+        for address in addresses {
+            if !from_addresses.contains(&address) {
+                from_addresses.push(address);
+            }
+            if !to_impersonate.contains(&address) {
+                to_impersonate.push(address);
+            }
+        }
+    }
+
+    // This fails (and they're left as node accounts) on nodes without the hardhat/anvil methods
+    for address in to_impersonate {
+        if rpc_request("hardhat_impersonateAccount", serde_json::json!([address]))
+            .await
+            .is_ok()
+        {
+            STATE.impersonated.write().unwrap().push(address);
+        }
+    }
+}
+
+// Genesis hash + block number, to tell a restarted node (e.g. anvil on the same url) apart
+// from the one we set up
+async fn node_identity(client_wrapper: &providers::ClientProviderWrapper) -> Result<(H256, U64)> {
+    let genesis_hash = client_wrapper
+        .client
+        .get_block(0u64)
+        .await?
+        .and_then(|genesis| genesis.hash)
+        .unwrap_or_default();
+    let block_number = client_wrapper.client.get_block_number().await?;
+    Ok((genesis_hash, block_number))
+}
+
+// Marks the connection as failed if the node went away, so it gets retried. A node that
+// came back on another chain gets a full reconnect; one restarted on the same chain (new
+// genesis, or the chain went backwards) has lost its impersonations, balances and
+// snapshots, so those are set up again.
+pub fn check_connection() {
+    wasm_bindgen_futures::spawn_local(async {
+        let res = async {
+            let client_wrapper = shared_state::read_shared_client()?;
+            let chain_id = client_wrapper.client.get_chainid().await?;
+            let identity = node_identity(&client_wrapper).await?;
+            Ok::<_, eyre::Report>((client_wrapper, chain_id, identity))
+        }
+        .await;
+
+        let (client_wrapper, chain_id, (genesis_hash, block_number)) = match res {
+            Ok(res) => res,
+            Err(e) => {
+                let mut connection = STATE.connection.write().unwrap();
+                if matches!(*connection, ConnectionStatus::Connected { .. }) {
+                    *connection = ConnectionStatus::Failed(e.to_string());
+                }
+                return;
+            }
+        };
+
+        if chain_id != client_wrapper.chain_id {
+            if let Some(profile) = shared_state::read_active_network() {
+                connect(profile);
+            }
+            return;
+        }
+
+        let restarted =
+            STATE
+                .node_identity
+                .read()
+                .unwrap()
+                .is_some_and(|(known_genesis, last_block)| {
+                    known_genesis != genesis_hash || block_number < last_block
+                });
+        *STATE.node_identity.write().unwrap() = Some((genesis_hash, block_number));
+        if restarted {
+            log!("node restarted, setting up accounts again");
+            STATE.snapshots.write().unwrap().clear();
+            STATE.balances.write().unwrap().clear();
+            setup_accounts(&client_wrapper).await;
+            refresh_balances();
+        }
+    });
+}
+
+pub fn impersonate_account(address: H160) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match rpc_request("hardhat_impersonateAccount", serde_json::json!([address])).await {
+            Ok(_) => {
+                let mut impersonated = STATE.impersonated.write().unwrap();
+                if !impersonated.contains(&address) {
+                    impersonated.push(address);
+                }
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+pub fn stop_impersonating_account(address: H160) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match rpc_request(
+            "hardhat_stopImpersonatingAccount",
+            serde_json::json!([address]),
+        )
+        .await
+        {
+            Ok(_) => {
+                STATE
+                    .impersonated
+                    .write()
+                    .unwrap()
+                    .retain(|a| *a != address);
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
     });
-    Ok(())
 }
 
 pub fn set_balance(address: H160, balance: U256) {
@@ -140,14 +614,13 @@ pub fn set_balance(address: H160, balance: U256) {
 
 pub fn set_storage_at(address: H160, slot: String, value: String) {
     wasm_bindgen_futures::spawn_local(async move {
-        let client_wrapper = shared_state::read_shared_client().unwrap();
-        let set_stor_params: Value = serde_json::json!([address, slot, value]);
+        let res = async {
+            let slot: H256 = slot.parse()?;
+            let value: H256 = value.parse()?;
+            set_storage(address, slot, value).await
+        }
+        .await;
 
-        let res = client_wrapper
-            .client
-            .provider()
-            .request::<Value, Value>("hardhat_setStorageAt", set_stor_params)
-            .await;
         match res {
             Ok(_) => {
                 send_ok_popup("Success".to_string());
@@ -159,6 +632,452 @@ pub fn set_storage_at(address: H160, slot: String, value: String) {
     });
 }
 
+pub async fn set_storage(address: H160, slot: H256, value: H256) -> Result<()> {
+    rpc_request(
+        "hardhat_setStorageAt",
+        serde_json::json!([address, slot, value]),
+    )
+    .await?;
+    Ok(())
+}
+
+pub fn get_nonce(address: H160) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            let client_wrapper = shared_state::read_shared_client()?;
+            Ok::<U256, eyre::Report>(
+                client_wrapper
+                    .client
+                    .get_transaction_count(address, None)
+                    .await?,
+            )
+        }
+        .await;
+
+        match res {
+            Ok(nonce) => {
+                send_ok_popup(format!("Nonce of {:#x}: {}", address, nonce));
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+// Handy for reproducing CREATE addresses of deployers from a fork
+pub fn set_nonce(address: H160, nonce: U256) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match rpc_request("hardhat_setNonce", serde_json::json!([address, nonce])).await {
+            Ok(_) => {
+                send_ok_popup(format!("Set nonce of {:#x} to {}", address, nonce));
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+// Time travel; these all refresh the latest block afterwards so the panel updates
+pub fn increase_time(seconds: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        // evm_increaseTime only applies to the next block, so mine one to make it visible
+        let res = async {
+            rpc_request("evm_increaseTime", serde_json::json!([seconds])).await?;
+            rpc_request("evm_mine", serde_json::json!([])).await
+        }
+        .await;
+
+        match res {
+            Ok(_) => refresh_latest_block(),
+            Err(e) => send_error_popup(format!("ERROR: {}", e)),
+        }
+    });
+}
+
+pub fn set_next_block_timestamp(timestamp: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match rpc_request("evm_setNextBlockTimestamp", serde_json::json!([timestamp])).await {
+            Ok(_) => send_ok_popup(format!("Next block timestamp set to {}", timestamp)),
+            Err(e) => send_error_popup(format!("ERROR: {}", e)),
+        }
+    });
+}
+
+pub fn mine_blocks(blocks: u64, interval: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match mine(blocks, interval).await {
+            Ok(_) => refresh_latest_block(),
+            Err(e) => send_error_popup(format!("ERROR: {}", e)),
+        }
+    });
+}
+
+// Mines up to (and including) the given block number
+pub fn roll_to_block(target: u64) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            let client_wrapper = shared_state::read_shared_client()?;
+            let current = client_wrapper.client.get_block_number().await?.as_u64();
+            if target <= current {
+                return Err(eyre::eyre!(
+                    "Block {} is not ahead of the current block {}",
+                    target,
+                    current
+                ));
+            }
+            mine(target - current, 1).await
+        }
+        .await;
+
+        match res {
+            Ok(_) => refresh_latest_block(),
+            Err(e) => send_error_popup(format!("ERROR: {}", e)),
+        }
+    });
+}
+
+// anvil_mine does it in one request; nodes without it (e.g. hardhat) get one evm_mine per
+// block, with the interval applied through evm_increaseTime
+async fn mine(blocks: u64, interval: u64) -> Result<()> {
+    let params = serde_json::json!([U256::from(blocks), U256::from(interval)]);
+    if rpc_request("anvil_mine", params).await.is_ok() {
+        return Ok(());
+    }
+
+    for block in 0..blocks {
+        if block > 0 {
+            rpc_request("evm_increaseTime", serde_json::json!([interval])).await?;
+        }
+        rpc_request("evm_mine", serde_json::json!([])).await?;
+    }
+    Ok(())
+}
+
+pub fn refresh_latest_block() {
+    wasm_bindgen_futures::spawn_local(async {
+        let res = async {
+            let client_wrapper = shared_state::read_shared_client()?;
+            let latest = client_wrapper
+                .client
+                .provider()
+                .get_block(BlockNumber::Latest)
+                .await?;
+            Ok::<_, eyre::Report>(latest)
+        }
+        .await;
+
+        // No popup here since this gets polled
+        match res {
+            Ok(latest) => *STATE.latest_block.write().unwrap() = latest,
+            Err(e) => {
+                log!("couldn't get latest block: {}", e);
+            }
+        }
+    });
+}
+
+async fn rpc_request(method: &str, params: Value) -> Result<Value> {
+    let client_wrapper = shared_state::read_shared_client()?;
+    Ok(client_wrapper
+        .client
+        .provider()
+        .request::<Value, Value>(method, params)
+        .await?)
+}
+
+pub fn take_snapshot(label: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match evm_snapshot(label).await {
+            Ok(snapshot) => {
+                STATE.snapshots.write().unwrap().push(snapshot);
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+async fn evm_snapshot(label: String) -> Result<Snapshot> {
+    let client_wrapper = shared_state::read_shared_client()?;
+
+    let id = client_wrapper
+        .client
+        .provider()
+        .request::<Value, String>("evm_snapshot", serde_json::json!([]))
+        .await?;
+    let block_number = client_wrapper.client.get_block_number().await?;
+
+    Ok(Snapshot {
+        id,
+        label,
+        block_number,
+        taken_at: String::from(js_sys::Date::new_0().to_locale_time_string("en-US")),
+    })
+}
+
+// Reverting consumes the snapshot (and any taken after it), so those get dropped along with
+// any contracts whose code the revert removed and all the now-stale outputs
+pub fn revert_to_snapshot(snapshot: Snapshot) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = match shared_state::read_shared_client() {
+            Ok(client_wrapper) => client_wrapper
+                .client
+                .provider()
+                .request::<Value, bool>("evm_revert", serde_json::json!([snapshot.id]))
+                .await
+                .map_err(eyre::Report::from),
+            Err(e) => Err(e),
+        };
+
+        match res {
+            Ok(true) => {
+                let mut snapshots = STATE.snapshots.write().unwrap();
+                if let Some(idx) = snapshots.iter().position(|s| s.id == snapshot.id) {
+                    snapshots.truncate(idx);
+                }
+                drop(snapshots);
+
+                // Not a restart, see check_connection
+                if let Some((_, last_block)) = STATE.node_identity.write().unwrap().as_mut() {
+                    *last_block = snapshot.block_number;
+                }
+
+                let reverted_away = contracts_without_code().await;
+                let mut deployed_contracts = STATE.deployed_contracts.write().unwrap();
+                deployed_contracts
+                    .retain(|_, deployed| !reverted_away.contains(&deployed.address_h160));
+                for deployed in deployed_contracts.values_mut() {
+                    deployed.func_output.clear();
+                    deployed.state_rows = None;
+                    deployed.receipt_logs.clear();
+                    deployed.historical_logs.clear();
+                    deployed.history_from_block = None;
+                }
+
+                STATE.temp_fn_output.write().unwrap().clear();
+                STATE.temp_receipt_logs.write().unwrap().clear();
+                STATE.temp_historical_logs.write().unwrap().clear();
+
+                send_ok_popup(format!(
+                    "Reverted to {:?} (block {})",
+                    snapshot.label, snapshot.block_number
+                ));
+            }
+            Ok(false) => {
+                send_error_popup(format!(
+                    "ERROR: Node doesn't know snapshot {} (restarted or already reverted?)",
+                    snapshot.id
+                ));
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+// Contracts that had code when added but have none now, i.e. were deployed after the snapshot
+// that was just reverted to. Going by code rather than block number keeps contracts that
+// were loaded (e.g. on a fork) after the snapshot was taken but existed before it.
+async fn contracts_without_code() -> Vec<H160> {
+    let client_wrapper = match shared_state::read_shared_client() {
+        Ok(client_wrapper) => client_wrapper,
+        Err(_) => return vec![],
+    };
+    let addresses: Vec<H160> = STATE
+        .deployed_contracts
+        .read()
+        .unwrap()
+        .values()
+        .filter(|deployed| !deployed.bytecode_b.is_empty())
+        .map(|deployed| deployed.address_h160)
+        .collect();
+
+    let mut without_code = Vec::new();
+    for address in addresses {
+        // Keep the contract if we can't tell
+        if let Ok(code) = client_wrapper.client.get_code(address, None).await {
+            if code.is_empty() {
+                without_code.push(address);
+            }
+        }
+    }
+    without_code
+}
+
+// Replaces the runtime code at an address, then (re)loads it as a deployed contract using the
+// given artifact's ABI. Immutables in compiled code will be left zeroed.
+pub fn set_code_wrapper(address: String, code: String, compiled: Option<CompiledContract>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            set_code(&address, &code).await?;
+            load_at_address(compiled, address.trim().to_string()).await
+        }
+        .await;
+
+        match res {
+            Ok(result) => {
+                send_ok_popup(format!("Set code at {}", result.address_string));
+                STATE
+                    .deployed_contracts
+                    .write()
+                    .unwrap()
+                    .insert(result.address_h160.to_string(), result);
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+async fn set_code(address: &str, code: &str) -> Result<()> {
+    let address: H160 = address
+        .trim()
+        .parse()
+        .map_err(|e| eyre::eyre!("Invalid address {:?}: {}", address, e))?;
+    let code = code.trim();
+    let code_bytes =
+        crate::abi::parse_bytes_input(&format!("0x{}", code.strip_prefix("0x").unwrap_or(code)))?;
+
+    rpc_request(
+        "hardhat_setCode",
+        serde_json::json!([address, Bytes::from(code_bytes)]),
+    )
+    .await?;
+    Ok(())
+}
+
+// Batches staticcalls into a single eth_call through Multicall3, returning (success, return
+// data) for each call in order. Calls are made from the Multicall3 contract, so anything that
+// depends on msg.sender will see that rather than the selected "from" address.
+pub async fn aggregate3(
+    calls: &[(H160, Bytes)],
+    tx_configs: &TxConfigs,
+) -> Result<Vec<(bool, Bytes)>> {
+    ensure_multicall3().await?;
+    let client_wrapper = shared_state::read_shared_client()?;
+
+    let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::default());
+    tx.set_to(multicall::MULTICALL_ADDRESS);
+    tx.set_data(multicall::encode_aggregate3(calls));
+    utils::apply_tx_configs(&mut tx, tx_configs)?;
+
+    let return_data = client_wrapper.client.provider().call_raw(&tx).await?;
+    multicall::decode_aggregate3(&return_data)
+}
+
+// Etches Multicall3 if the chain doesn't have it, preferring a compiled Multicall3 from the
+// project over our minimal aggregate3-only version. Only works on anvil/hardhat.
+async fn ensure_multicall3() -> Result<()> {
+    let client_wrapper = shared_state::read_shared_client()?;
+    let code = client_wrapper
+        .client
+        .get_code(multicall::MULTICALL_ADDRESS, None)
+        .await?;
+    if !code.is_empty() {
+        return Ok(());
+    }
+
+    let compiled_code = STATE
+        .compiled_contracts
+        .read()
+        .unwrap()
+        .values()
+        .find(|compiled| compiled.contract_name == "Multicall3")
+        .and_then(|compiled| compiled.deployed_bytecode());
+    let code = compiled_code.unwrap_or_else(|| multicall::FALLBACK_MULTICALL3_CODE.to_string());
+
+    set_code(&format!("{:#x}", multicall::MULTICALL_ADDRESS), &code)
+        .await
+        .map_err(|e| {
+            eyre::eyre!(
+                "No Multicall3 at {:#x} and couldn't etch one: {}",
+                multicall::MULTICALL_ADDRESS,
+                e
+            )
+        })
+}
+
+// `to_block` is set when paging further back than the last fetch reached
+pub fn get_logs_wrapper(
+    address: H160,
+    abi: Value,
+    from_block: U64,
+    to_block: Option<U64>,
+    event_topic: Option<H256>,
+) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match get_logs(address, from_block, to_block, event_topic).await {
+            Ok((logs, searched_from)) => {
+                // Decode against the contract's ABI first, then anything else we've loaded
+                let mut abis = vec![abi];
+                abis.extend(shared_state::read_known_abis());
+                let logs = logs
+                    .into_iter()
+                    .map(|log| DecodedLog::new(log, &abis))
+                    .collect();
+
+                STATE.temp_historical_logs.write().unwrap().insert(
+                    format!("{:#x}", address),
+                    LogPage {
+                        logs,
+                        searched_from,
+                        older: to_block.is_some(),
+                    },
+                );
+            }
+            Err(e) => {
+                send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+// Most RPCs (forks especially) cap the block range of a single eth_getLogs, so the range is
+// walked in chunks and never reaches back further than MAX_LOG_BLOCKS from its end. Older
+// blocks take another fetch.
+const LOG_CHUNK_BLOCKS: u64 = 10_000;
+pub const MAX_LOG_BLOCKS: u64 = 100_000;
+
+// The logs, and the first block they were searched from
+async fn get_logs(
+    address: H160,
+    from_block: U64,
+    to_block: Option<U64>,
+    event_topic: Option<H256>,
+) -> Result<(Vec<Log>, u64)> {
+    let client_wrapper = shared_state::read_shared_client()?;
+
+    let latest = match to_block {
+        Some(to_block) => to_block.as_u64(),
+        None => client_wrapper.client.get_block_number().await?.as_u64(),
+    };
+    let searched_from = from_block
+        .as_u64()
+        .max(latest.saturating_sub(MAX_LOG_BLOCKS));
+    let mut chunk_start = searched_from;
+
+    let mut logs: Vec<Log> = Vec::new();
+    while chunk_start <= latest {
+        let chunk_end = (chunk_start + LOG_CHUNK_BLOCKS - 1).min(latest);
+        let mut filter = Filter::new()
+            .address(address)
+            .from_block(chunk_start)
+            .to_block(chunk_end);
+        if let Some(topic) = event_topic {
+            filter = filter.topic0(topic);
+        }
+        logs.extend(client_wrapper.client.get_logs(&filter).await?);
+        chunk_start = chunk_end + 1;
+    }
+
+    Ok((logs, searched_from))
+}
+
 pub fn load_at_address_wrapper(compiled: Option<CompiledContract>, address: String) {
     wasm_bindgen_futures::spawn_local(async {
         match load_at_address(compiled, address).await {
@@ -210,6 +1129,35 @@ async fn load_at_address(
 
     // log!("bytecode str {:?}", bytecode_ascii);
 
+    // Without a compiled contract, try to recognize the code from the project's artifacts. An
+    // exact match is loaded in full; partial matches are left for the user to pick from
+    let bytecode_matches = match compiled {
+        Some(_) => vec![],
+        None => {
+            load_artifact_codes().await;
+            STATE
+                .artifact_index
+                .read()
+                .unwrap()
+                .match_runtime_code(&bytecode_b)
+        }
+    };
+    let exact_match = bytecode_matches
+        .first()
+        .filter(|bytecode_match| bytecode_match.is_exact())
+        .cloned();
+    let compiled = match (compiled, exact_match) {
+        (Some(compiled), _) => Some(compiled),
+        (None, Some(exact_match)) => match load_artifact(exact_match.artifact_path).await {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                log!("couldn't load the matching artifact: {}", e);
+                None
+            }
+        },
+        (None, None) => None,
+    };
+
     // Handle the case where we're loading a compiled contract and the case where
     // we don't have an ABI (e.g. so we can easily hit an address w/ raw calldata)
     let solc_json = match compiled.clone() {
@@ -250,7 +1198,7 @@ async fn load_at_address(
     // 7. get the contract's address; should be same but also if it fails there's an issue
     let addr = contract_instance.address().to_owned();
 
-    let name = match compiled.clone() {
+    let name = match &compiled {
         Some(c) => c.contract_name.clone(),
         None => "Unknown".to_string(),
     };
@@ -264,6 +1212,12 @@ async fn load_at_address(
         address_h160: addr,
         address_string: format!("{:#x}", addr),
         compiled_contract: compiled.clone(),
+        proxy: proxy::detect(addr, &bytecode_b).await,
+        // an exact match that couldn't be loaded is still worth offering
+        bytecode_matches: bytecode_matches
+            .into_iter()
+            .filter(|bytecode_match| compiled.is_none() || !bytecode_match.is_exact())
+            .collect(),
         deployed_block: latest,
         ..Default::default()
     };
@@ -271,34 +1225,74 @@ async fn load_at_address(
     Ok(deployed_contract)
 }
 
-pub fn deploy_wrapper(compiled: CompiledContract, constructor_args: Vec<String>) {
+pub fn deploy_wrapper(
+    compiled: CompiledContract,
+    constructor_args: Vec<String>,
+    tx_configs: TxConfigs,
+) {
     log!("in deploy wrapper");
 
-    wasm_bindgen_futures::spawn_local(async {
-        let result = deploy(compiled, constructor_args).await;
+    let mut record = TxRecord::new(
+        TxKind::Deploy,
+        H160::zero(),
+        compiled.contract_name.clone(),
+        format!("constructor({})", constructor_args.join(", ")),
+        tx_configs.clone(),
+        TxReplay::Deploy {
+            file_path: compiled.file_path.clone(),
+            constructor_args: constructor_args.clone(),
+        },
+    );
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = deploy(compiled, constructor_args, tx_configs).await;
         match result {
-            Ok(contract) => {
+            Ok((contract, receipt)) => {
                 log!("deployed contract: {:?}", contract.address_string);
+                store_deploy_record(&mut record, &contract, receipt);
                 STATE
                     .deployed_contracts
                     .write()
                     .unwrap()
                     .insert(contract.address_h160.to_string(), contract.clone());
             }
-            Err(e) => send_error_popup(format!("ERROR: {}", e)),
+            Err(e) => {
+                record.error = Some(e.to_string());
+                send_error_popup(format!("ERROR: {}", e));
+            }
         }
+        shared_state::push_tx_record(record);
     });
 }
 
+// Fills in a deploy's history entry from the new contract + its receipt
+fn store_deploy_record(
+    record: &mut TxRecord,
+    contract: &DeployedContract,
+    receipt: Option<TransactionReceipt>,
+) {
+    record.target = contract.address_h160;
+    if let Some(receipt) = &receipt {
+        let mut abis = vec![contract.abi.clone()];
+        abis.extend(shared_state::read_known_abis());
+        record.logs = receipt
+            .logs
+            .iter()
+            .map(|log| DecodedLog::new(log.clone(), &abis))
+            .collect();
+    }
+    record.result.tx_receipt = receipt;
+}
+
 async fn deploy(
     compiled: CompiledContract,
     constructor_args: Vec<String>,
-) -> Result<DeployedContract, Box<dyn std::error::Error>> {
+    tx_configs: TxConfigs,
+) -> Result<(DeployedContract, Option<TransactionReceipt>), Box<dyn std::error::Error>> {
     log!("in deploy");
 
     let client_wrapper = shared_state::read_shared_client()?;
-    let client = client_wrapper.client;
-    let tx_configs = shared_state::read_tx_configs();
+    let client = client_wrapper.client_for(tx_configs.from_address);
 
     let solc_json = compiled.compiled_json.clone();
     let abi = serde_json::to_string(&solc_json["abi"]).unwrap();
@@ -320,16 +1314,10 @@ async fn deploy(
     // 6. deploy it with the constructor arguments
     let mut deployer = factory.deploy_tokens(tokens)?;
 
-    deployer.tx.set_from(tx_configs.from_address);
-
-    deployer
-        .tx
-        .set_value(utils::eth_str_to_u256_wei(&tx_configs.value)?);
-
-    deployer.tx.set_gas_price(tx_configs.gas_price);
+    utils::apply_tx_configs(&mut deployer.tx, &tx_configs)?;
 
     log!("deployer: {:?}", deployer);
-    let contract = deployer.send().await?;
+    let (contract, receipt) = deployer.send_with_receipt().await?;
     log!("contract! {:?}", contract);
     let latest = client
         .provider()
@@ -339,13 +1327,15 @@ async fn deploy(
     // 7. get the contract's address
     let addr = contract.address().to_owned();
     log!("deployed! {:?}", addr);
+    // What the constructor left at the address (immutables filled in), not the creation code
+    let runtime_code = client.provider().get_code(addr, None).await?;
 
     // Create deployed contract and return it
     let deployed_contract = DeployedContract {
         name: compiled.contract_name.clone(),
         abi: solc_json["abi"].clone(),
-        bytecode_b,
-        bytecode_string: bytecode_ascii,
+        bytecode_string: hex::encode(runtime_code.as_ref()),
+        bytecode_b: runtime_code,
         address_h160: addr,
         address_string: format!("{:#x}", addr),
         compiled_contract: Some(compiled),
@@ -353,33 +1343,51 @@ async fn deploy(
         ..Default::default()
     };
 
-    Ok(deployed_contract)
+    Ok((deployed_contract, Some(receipt)))
 }
 
-pub fn deploy_raw_bytecode_wrapper(bytecode_string: String) {
+pub fn deploy_raw_bytecode_wrapper(bytecode_string: String, tx_configs: TxConfigs) {
     log!("in deploy wrapper");
 
-    wasm_bindgen_futures::spawn_local(async {
-        let result = deploy_raw_bytecode(bytecode_string).await;
+    let mut record = TxRecord::new(
+        TxKind::Deploy,
+        H160::zero(),
+        "Unknown".to_string(),
+        "raw bytecode".to_string(),
+        tx_configs.clone(),
+        TxReplay::DeployRaw {
+            bytecode: bytecode_string.clone(),
+        },
+    );
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = deploy_raw_bytecode(bytecode_string, tx_configs).await;
         match result {
-            Ok(contract) => {
+            Ok((contract, receipt)) => {
                 log!("deployed contract: {:?}", contract.address_string);
+                store_deploy_record(&mut record, &contract, receipt);
                 STATE
                     .deployed_contracts
                     .write()
                     .unwrap()
                     .insert(contract.address_h160.to_string(), contract.clone());
             }
-            Err(e) => send_error_popup(format!("ERROR: {:?}", e)),
+            Err(e) => {
+                record.error = Some(e.to_string());
+                send_error_popup(format!("ERROR: {:?}", e));
+            }
         }
+        shared_state::push_tx_record(record);
     });
 }
 
-async fn deploy_raw_bytecode(bytecode_ascii: String) -> Result<DeployedContract> {
+async fn deploy_raw_bytecode(
+    bytecode_ascii: String,
+    tx_configs: TxConfigs,
+) -> Result<(DeployedContract, Option<TransactionReceipt>)> {
     log!("in deploy");
     let client_wrapper = shared_state::read_shared_client()?;
-    let client = client_wrapper.client;
-    let tx_configs = shared_state::read_tx_configs();
+    let client = client_wrapper.client_for(tx_configs.from_address);
 
     // Decode the provided bytecode
     let bytecode_b = ethers::types::Bytes::from(hex::decode(bytecode_ascii.clone())?);
@@ -395,26 +1403,31 @@ async fn deploy_raw_bytecode(bytecode_ascii: String) -> Result<DeployedContract>
 
     let mut deployer = factory.deploy(())?;
 
-    deployer.tx.set_from(tx_configs.from_address);
-    deployer
-        .tx
-        .set_value(utils::eth_str_to_u256_wei(&tx_configs.value)?);
-    deployer.tx.set_gas_price(tx_configs.gas_price);
-    let contract = deployer.send().await?;
+    utils::apply_tx_configs(&mut deployer.tx, &tx_configs)?;
+    let (contract, receipt) = deployer.send_with_receipt().await?;
     log!("contract: {:?}", contract);
+    // Get the latest block for sorting (and dropping it on snapshot revert)
+    let latest = client
+        .provider()
+        .get_block(BlockNumber::Latest)
+        .await?
+        .unwrap_or_default();
     // 7. get the contract's address
     let addr = contract.address().to_owned();
     log!("deployed! {:?}", addr);
+    // The input was creation code, keep what it returned
+    let runtime_code = client.provider().get_code(addr, None).await?;
 
     // Create and return a DeployedContract
     let deployed_contract = DeployedContract {
         name: "Unknown".to_string(),
-        bytecode_b: bytecode_b.clone(),
-        bytecode_string: bytecode_ascii.to_string(),
+        bytecode_string: hex::encode(runtime_code.as_ref()),
+        bytecode_b: runtime_code,
         address_h160: addr,
         address_string: format!("{:#x}", addr),
+        deployed_block: latest,
         ..Default::default()
     };
 
-    Ok(deployed_contract)
+    Ok((deployed_contract, Some(receipt)))
 }
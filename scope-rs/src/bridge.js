@@ -25,6 +25,19 @@ export function get_open_files() {
   });
 }
 
+export function get_artifact_files() {
+  vscode.postMessage({
+    command: "get_artifact_files",
+  });
+}
+
+export function get_artifact_codes(artifactPaths) {
+  vscode.postMessage({
+    command: "get_artifact_codes",
+    data: { artifactPaths: artifactPaths },
+  });
+}
+
 export function get_file_contents(filePath) {
   vscode.postMessage({
     command: "get_file_contents",
@@ -39,6 +52,13 @@ export function get_compiled_solidity(filePath) {
   });
 }
 
+export function get_artifact(filePath) {
+  vscode.postMessage({
+    command: "get_artifact",
+    data: { filePath: filePath },
+  });
+}
+
 export function forge_build(command) {
   vscode.postMessage({
     command: "forge_build",
@@ -46,6 +66,11 @@ export function forge_build(command) {
   });
 }
 
+// Lets a long synchronous task (e.g. scrypt) wait a frame so the UI can paint first
+export function sleep(ms) {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
 export function execute_shell_command(command) {
   vscode.postMessage({
     command: "execute_shell_command",
@@ -78,6 +103,18 @@ window.addEventListener("message", (event) => {
       wasm.receive_open_file_paths(message.content);
       break;
 
+    case "post_artifact_files":
+      wasm.receive_artifact_files(
+        message.content.artifactPaths,
+        message.content.buildInfos,
+        message.content.storageLayouts
+      );
+      break;
+
+    case "post_artifact_codes":
+      wasm.receive_artifact_codes(message.content);
+      break;
+
     case "post_file_contents":
       wasm.receive_file_contents(message.content.fileContents); // yuck
       break;
@@ -86,6 +123,10 @@ window.addEventListener("message", (event) => {
       wasm.receive_compiled_solidity(message.content.compiledJson, message.content.filePath);
       break;
 
+    case "post_artifact":
+      wasm.receive_artifact(message.content.artifactJson, message.content.filePath);
+      break;
+
     case "completed_forge_build":
       wasm.handle_completed_forge_build();
       break;
@@ -0,0 +1,479 @@
+use crate::artifacts::ArtifactCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Partial matches below this similarity aren't worth offering
+pub const PARTIAL_MATCH_THRESHOLD: f32 = 0.5;
+const MAX_CANDIDATES: usize = 5;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BytecodeMatch {
+    pub artifact_path: String,
+    pub display_name: String,
+    pub contract_name: String,
+    pub similarity: f32, // share of (masked) bytes that agree, 1.0 for an exact match
+}
+
+impl BytecodeMatch {
+    pub fn is_exact(&self) -> bool {
+        self.similarity >= 1.0
+    }
+}
+
+// Length of the CBOR metadata solc (and vyper) append to runtime code, including the 2 byte
+// big-endian length that ends the code. None if the tail doesn't look like a CBOR map.
+pub fn metadata_length(code: &[u8]) -> Option<usize> {
+    // A length longer than the code (or code too short to have one) is just code
+    let length_bytes = code.get(code.len().checked_sub(2)?..)?;
+    let cbor_length = u16::from_be_bytes([length_bytes[0], length_bytes[1]]) as usize;
+    let start = code.len().checked_sub(cbor_length + 2)?;
+    match code.get(start)? {
+        0xa0..=0xbf if cbor_length > 0 => Some(cbor_length + 2),
+        _ => None,
+    }
+}
+
+// The code without its metadata tail, which changes with any comment or path edit
+pub fn strip_metadata(code: &[u8]) -> &[u8] {
+    match metadata_length(code) {
+        Some(length) => &code[..code.len() - length],
+        None => code,
+    }
+}
+
+// Artifact code with the unlinked library placeholders zeroed out so it decodes
+pub fn artifact_code_bytes(artifact_code: &ArtifactCode) -> Option<Vec<u8>> {
+    let mut code_hex = artifact_code
+        .deployed_bytecode
+        .trim_start_matches("0x")
+        .to_string();
+    for (start, length) in artifact_code.masked.iter() {
+        let range = start * 2..(start + length) * 2;
+        if range.end <= code_hex.len() && code_hex.is_char_boundary(range.start) {
+            code_hex.replace_range(range, &"0".repeat(length * 2));
+        }
+    }
+    hex::decode(code_hex).ok()
+}
+
+// A bytecode/deployedBytecode object from an artifact, with its unlinked library placeholders
+// (`__$<hash>$__`) and immutables zeroed
+pub fn decode_unlinked(bytecode: &Value) -> Option<Vec<u8>> {
+    artifact_code_bytes(&ArtifactCode {
+        deployed_bytecode: bytecode["object"].as_str()?.to_string(),
+        masked: masked_ranges(bytecode),
+        ..Default::default()
+    })
+}
+
+// (start, length) of the immutables and library links in a bytecode/deployedBytecode object,
+// same as the extension sends along with artifact codes
+pub fn masked_ranges(bytecode: &Value) -> Vec<(usize, usize)> {
+    let immutables = bytecode["immutableReferences"]
+        .as_object()
+        .into_iter()
+        .flat_map(|references| references.values());
+    let links = bytecode["linkReferences"]
+        .as_object()
+        .into_iter()
+        .flat_map(|by_file| by_file.values())
+        .filter_map(|by_name| by_name.as_object())
+        .flat_map(|by_name| by_name.values());
+    immutables
+        .chain(links)
+        .filter_map(|ranges| ranges.as_array())
+        .flatten()
+        .filter_map(|range| {
+            Some((
+                range["start"].as_u64()? as usize,
+                range["length"].as_u64()? as usize,
+            ))
+        })
+        .collect()
+}
+
+// Byte by byte agreement between the stripped codes, with the masked ranges (immutables and
+// library addresses, which only get filled in on deployment) always agreeing
+pub fn similarity(onchain: &[u8], artifact: &[u8], masked: &[(usize, usize)]) -> f32 {
+    let longest = onchain.len().max(artifact.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let is_masked = |idx: usize| {
+        masked
+            .iter()
+            .any(|(start, length)| idx >= *start && idx < start + length)
+    };
+    let agreeing = onchain
+        .iter()
+        .zip(artifact.iter())
+        .enumerate()
+        .filter(|(idx, (a, b))| a == b || is_masked(*idx))
+        .count();
+    agreeing as f32 / longest as f32
+}
+
+// Every artifact whose runtime code matches the on-chain code well enough, best first. An
+// exact match, if there's one, makes the rest irrelevant so it's returned alone.
+pub fn rank_matches<'a>(
+    code: &[u8],
+    artifact_codes: impl Iterator<Item = (&'a ArtifactCode, String, String)>,
+) -> Vec<BytecodeMatch> {
+    let onchain = strip_metadata(code);
+    if onchain.is_empty() {
+        return vec![];
+    }
+
+    let mut matches: Vec<BytecodeMatch> = Vec::new();
+    for (artifact_code, display_name, contract_name) in artifact_codes {
+        let Some(artifact) = artifact_code_bytes(artifact_code) else {
+            continue;
+        };
+        let artifact = strip_metadata(&artifact);
+        // Can't reach the threshold with this much of a size difference, skip the compare
+        let shortest = onchain.len().min(artifact.len()) as f32;
+        if shortest / (onchain.len().max(artifact.len()) as f32) < PARTIAL_MATCH_THRESHOLD {
+            continue;
+        }
+        let similarity = similarity(onchain, artifact, &artifact_code.masked);
+        if similarity >= PARTIAL_MATCH_THRESHOLD {
+            matches.push(BytecodeMatch {
+                artifact_path: artifact_code.artifact_path.clone(),
+                display_name,
+                contract_name,
+                similarity,
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    if let Some(exact) = matches.iter().find(|m| m.is_exact()) {
+        return vec![exact.clone()];
+    }
+    matches.truncate(MAX_CANDIDATES);
+    matches
+}
+
+// EIP-170 runtime code and EIP-3860 initcode size limits
+pub const MAX_CODE_SIZE: usize = 24576;
+pub const MAX_INITCODE_SIZE: usize = 49152;
+
+// What solc puts in the CBOR metadata tail; only the version is always there
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Metadata {
+    pub solc: Option<String>,
+    pub ipfs: Option<String>,  // base58 CIDv0 of the metadata json
+    pub bzzr0: Option<String>, // swarm hashes from older compilers
+    pub bzzr1: Option<String>,
+    pub experimental: bool,
+    pub other: Vec<String>, // keys we don't know about
+}
+
+// Decodes the metadata tail, if the code has one solc would have written: a map of text keys
+// to byte strings, text or booleans
+pub fn decode_metadata(code: &[u8]) -> Option<Metadata> {
+    let length = metadata_length(code)?;
+    let mut cbor = CborReader {
+        data: &code[code.len() - length..code.len() - 2],
+        position: 0,
+    };
+
+    let (major, entries) = cbor.head()?;
+    if major != 5 {
+        return None;
+    }
+    let mut metadata = Metadata::default();
+    for _ in 0..entries {
+        let CborValue::Text(key) = cbor.value()? else {
+            return None;
+        };
+        match (key.as_str(), cbor.value()?) {
+            // releases are [major, minor, patch], prereleases the full version string
+            ("solc", CborValue::Bytes(version)) if version.len() == 3 => {
+                metadata.solc = Some(format!("{}.{}.{}", version[0], version[1], version[2]))
+            }
+            ("solc", CborValue::Text(version)) => metadata.solc = Some(version),
+            ("ipfs", CborValue::Bytes(hash)) => {
+                metadata.ipfs = Some(bs58::encode(hash).into_string())
+            }
+            ("bzzr0", CborValue::Bytes(hash)) => metadata.bzzr0 = Some(hex::encode(hash)),
+            ("bzzr1", CborValue::Bytes(hash)) => metadata.bzzr1 = Some(hex::encode(hash)),
+            ("experimental", CborValue::Bool(experimental)) => metadata.experimental = experimental,
+            (key, _) => metadata.other.push(key.to_string()),
+        }
+    }
+    Some(metadata)
+}
+
+enum CborValue {
+    Bytes(Vec<u8>),
+    Text(String),
+    Bool(bool),
+    Uint,
+}
+
+// Just enough CBOR for solc's metadata; anything else is treated as malformed
+struct CborReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl CborReader<'_> {
+    fn take(&mut self, length: usize) -> Option<&[u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(length)?)?;
+        self.position += length;
+        Some(bytes)
+    }
+
+    // (major type, argument)
+    fn head(&mut self) -> Option<(u8, u64)> {
+        let initial = self.take(1)?[0];
+        let argument = match initial & 0x1f {
+            info @ 0..=23 => info as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().ok()?) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().ok()?) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().ok()?),
+            _ => return None,
+        };
+        Some((initial >> 5, argument))
+    }
+
+    fn value(&mut self) -> Option<CborValue> {
+        match self.head()? {
+            (0, _) => Some(CborValue::Uint),
+            (2, length) => Some(CborValue::Bytes(
+                self.take(usize::try_from(length).ok()?)?.to_vec(),
+            )),
+            (3, length) => Some(CborValue::Text(
+                String::from_utf8(self.take(usize::try_from(length).ok()?)?.to_vec()).ok()?,
+            )),
+            (7, 20) => Some(CborValue::Bool(false)),
+            (7, 21) => Some(CborValue::Bool(true)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const IPFS_DIGEST: &str = "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+
+    // What solc >= 0.6 appends: {"ipfs": <sha2-256 multihash>, "solc": [0, 8, 19]}, 0x0033
+    fn solc_tail(digest: &str) -> Vec<u8> {
+        let mut tail = hex::decode("a264697066735822").unwrap();
+        tail.extend(hex::decode(format!("1220{}", digest)).unwrap());
+        tail.extend(hex::decode("64736f6c634300081300").unwrap());
+        tail.push(0x33);
+        tail
+    }
+
+    // PUSH1 0x80 PUSH1 0x40 MSTORE ... STOP INVALID
+    fn runtime(body: &[u8], digest: &str) -> Vec<u8> {
+        let mut code = hex::decode("6080604052").unwrap();
+        code.extend_from_slice(body);
+        code.extend([0x00, 0xfe]);
+        code.extend(solc_tail(digest));
+        code
+    }
+
+    fn artifact(artifact_path: &str, code: &[u8], masked: Vec<(usize, usize)>) -> ArtifactCode {
+        ArtifactCode {
+            artifact_path: artifact_path.to_string(),
+            deployed_bytecode: format!("0x{}", hex::encode(code)),
+            masked,
+            ..Default::default()
+        }
+    }
+
+    fn rank(code: &[u8], artifacts: &[ArtifactCode]) -> Vec<BytecodeMatch> {
+        rank_matches(
+            code,
+            artifacts.iter().map(|artifact| {
+                (
+                    artifact,
+                    artifact.artifact_path.clone(),
+                    artifact.artifact_path.clone(),
+                )
+            }),
+        )
+    }
+
+    #[test]
+    fn solc_metadata_tail() {
+        let code = runtime(&[0x5b; 8], IPFS_DIGEST);
+        assert_eq!(solc_tail(IPFS_DIGEST).len(), 0x35);
+        assert_eq!(metadata_length(&code), Some(0x35));
+        assert_eq!(strip_metadata(&code), &code[..code.len() - 0x35]);
+
+        let metadata = decode_metadata(&code).unwrap();
+        assert_eq!(metadata.solc.as_deref(), Some("0.8.19"));
+        assert_eq!(
+            metadata.ipfs.as_deref(),
+            Some("QmNQatwxYrvx45JHzALe54be3KTBVQrLtHdPfkmvNNhQkw")
+        );
+        assert!(metadata.bzzr0.is_none() && metadata.bzzr1.is_none());
+        assert!(!metadata.experimental);
+        assert!(metadata.other.is_empty());
+    }
+
+    #[test]
+    fn older_and_prerelease_metadata() {
+        // 0.5.x: {"bzzr0": <32 bytes>}, 0x0029
+        let mut code = hex::decode("6080604052a165627a7a723058").unwrap();
+        code.push(0x20);
+        code.extend(hex::decode(IPFS_DIGEST).unwrap());
+        code.extend([0x00, 0x29]);
+        let metadata = decode_metadata(&code).unwrap();
+        assert_eq!(metadata.bzzr0.as_deref(), Some(IPFS_DIGEST));
+        assert!(metadata.solc.is_none());
+
+        // {"experimental": true, "solc": "0.8.20-nightly"}, with an unknown key
+        let mut code = hex::decode("6080604052").unwrap();
+        let mut tail = hex::decode("a36c6578706572696d656e74616cf5").unwrap();
+        tail.extend(hex::decode("64736f6c636e302e382e32302d6e696768746c79").unwrap());
+        tail.extend(hex::decode("6378797a01").unwrap());
+        code.extend(&tail);
+        code.extend((tail.len() as u16).to_be_bytes());
+        let metadata = decode_metadata(&code).unwrap();
+        assert!(metadata.experimental);
+        assert_eq!(metadata.solc.as_deref(), Some("0.8.20-nightly"));
+        assert_eq!(metadata.other, vec!["xyz"]);
+    }
+
+    #[test]
+    fn truncated_metadata_tail() {
+        // the length claims more than there is
+        let tail = solc_tail(IPFS_DIGEST);
+        let truncated = &tail[10..];
+        assert_eq!(metadata_length(truncated), None);
+        assert_eq!(strip_metadata(truncated), truncated);
+        assert!(decode_metadata(truncated).is_none());
+
+        assert_eq!(metadata_length(&[0x60, 0x80, 0xff, 0xff]), None);
+        assert_eq!(metadata_length(&[0x33]), None);
+        assert_eq!(metadata_length(&[]), None);
+        assert!(strip_metadata(&[]).is_empty());
+        // the length alone
+        assert_eq!(metadata_length(&[0x00, 0x00]), None);
+    }
+
+    #[test]
+    fn garbage_metadata_tail() {
+        // doesn't start with a map, so it's all code
+        let code = hex::decode("6080604052600160020300050005").unwrap();
+        assert_eq!(metadata_length(&code), None);
+        assert_eq!(strip_metadata(&code), &code[..]);
+
+        // looks like a map, but the key's head is malformed
+        let code = hex::decode("6080604052a1ffff0003").unwrap();
+        assert_eq!(metadata_length(&code), Some(5));
+        assert!(decode_metadata(&code).is_none());
+
+        // a byte string longer than the tail
+        let code = hex::decode("a164697066735bffffffffffffffff000000000d").unwrap();
+        assert!(decode_metadata(&code).is_none());
+
+        // the map claims more entries than there are
+        let code = hex::decode("a364736f6c6343000813000b").unwrap();
+        assert!(decode_metadata(&code).is_none());
+    }
+
+    #[test]
+    fn similarity_with_masked_ranges() {
+        let code = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(similarity(&code, &code, &[]), 1.0);
+        assert_eq!(similarity(&[], &[], &[]), 0.0);
+
+        let immutable_filled = [1, 2, 9, 9, 5, 6, 7, 8];
+        assert_eq!(similarity(&immutable_filled, &code, &[]), 0.75);
+        assert_eq!(similarity(&immutable_filled, &code, &[(2, 2)]), 1.0);
+
+        // the extra bytes count against it
+        assert_eq!(similarity(&code, &code[..4], &[]), 0.5);
+    }
+
+    #[test]
+    fn exact_match_ignores_metadata_and_immutables() {
+        let body = [0x5b; 16];
+        let artifact_code = artifact("Token", &runtime(&body, IPFS_DIGEST), vec![(7, 4)]);
+
+        // built elsewhere (other metadata hash), with an immutable filled in
+        let mut onchain_body = body;
+        onchain_body[2..6].copy_from_slice(&[0xaa; 4]);
+        let onchain = runtime(&onchain_body, &"ff".repeat(32));
+
+        let matches = rank(
+            &onchain,
+            &[artifact("Other", &[0x60; 64], vec![]), artifact_code],
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].artifact_path, "Token");
+        assert!(matches[0].is_exact());
+    }
+
+    #[test]
+    fn partial_matches_are_ranked() {
+        let body: Vec<u8> = (0..40).collect();
+        let onchain = runtime(&body, IPFS_DIGEST);
+
+        let mut close = body.clone();
+        close[0] = 0xff;
+        let mut further = body.clone();
+        further[..10].copy_from_slice(&[0xff; 10]);
+        let mut unrelated = body.clone();
+        unrelated.iter_mut().for_each(|byte| *byte = 0xee);
+
+        let matches = rank(
+            &onchain,
+            &[
+                artifact("Further", &runtime(&further, IPFS_DIGEST), vec![]),
+                artifact("Unrelated", &runtime(&unrelated, IPFS_DIGEST), vec![]),
+                artifact("Close", &runtime(&close, IPFS_DIGEST), vec![]),
+                artifact("Tiny", &runtime(&[0x01], IPFS_DIGEST), vec![]),
+                artifact("Broken", b"not hex", vec![]),
+            ],
+        );
+        let names: Vec<&str> = matches
+            .iter()
+            .map(|bytecode_match| bytecode_match.artifact_path.as_str())
+            .collect();
+        assert_eq!(names, vec!["Close", "Further"]);
+        assert!(matches
+            .iter()
+            .all(|bytecode_match| !bytecode_match.is_exact()));
+
+        assert!(rank(&solc_tail(IPFS_DIGEST), &[]).is_empty());
+    }
+
+    #[test]
+    fn unlinked_code_is_zeroed() {
+        let placeholder = format!("__${}$__", "ab".repeat(17));
+        let bytecode = json!({
+            "object": format!("0x73{}3014{}", placeholder, "ff".repeat(4)),
+            "linkReferences": {
+                "src/Lib.sol": { "Lib": [{ "start": 1, "length": 20 }] }
+            },
+            "immutableReferences": {
+                "12": [{ "start": 23, "length": 2 }]
+            }
+        });
+        assert_eq!(masked_ranges(&bytecode), vec![(23, 2), (1, 20)]);
+
+        let mut expected = vec![0x73];
+        expected.extend([0u8; 20]);
+        expected.extend([0x30, 0x14, 0x00, 0x00, 0xff, 0xff]);
+        assert_eq!(decode_unlinked(&bytecode), Some(expected));
+
+        assert_eq!(
+            decode_unlinked(&json!({ "object": "0x6080" })),
+            Some(vec![0x60, 0x80])
+        );
+        assert_eq!(decode_unlinked(&json!({})), None);
+    }
+}
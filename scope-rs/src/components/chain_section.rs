@@ -0,0 +1,129 @@
+use crate::{backend, shared_state::STATE, utils};
+
+// How often to poll for the latest block while the panel is open (ms)
+const REFRESH_INTERVAL: f64 = 2000.0;
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct ChainSection {
+    warp_input: String,
+    timestamp_input: String,
+    mine_blocks_input: String,
+    mine_interval_input: String,
+    roll_input: String,
+
+    #[serde(skip)]
+    last_refresh: f64,
+}
+
+impl ChainSection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Chain Controls", |ui| {
+            self.render_latest_block(ui);
+            self.render_time_controls(ui);
+            self.render_mine_controls(ui);
+        });
+    }
+
+    fn render_latest_block(&mut self, ui: &mut egui::Ui) {
+        // While live, new blocks come in over the ws subscription instead
+        let now = js_sys::Date::now();
+        if !*STATE.live_updates.read().unwrap() && now - self.last_refresh > REFRESH_INTERVAL {
+            backend::refresh_latest_block();
+            self.last_refresh = now;
+        }
+
+        match &*STATE.latest_block.read().unwrap() {
+            Some(block) => {
+                let timestamp = block.timestamp.low_u64();
+                let date = js_sys::Date::new(&((timestamp * 1000) as f64).into());
+                ui.label(format!(
+                    "Block: {}  Timestamp: {} ({})",
+                    block.number.unwrap_or_default(),
+                    timestamp,
+                    String::from(date.to_iso_string())
+                ));
+            }
+            None => {
+                ui.label("Block: -");
+            }
+        }
+    }
+
+    fn render_time_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Warp +").clicked() {
+                match utils::parse_duration_secs(&self.warp_input) {
+                    Ok(seconds) => backend::increase_time(seconds),
+                    Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
+                }
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.warp_input)
+                    .hint_text("seconds (or 30m, 12h, 7d, 2w)")
+                    .desired_width(ui.available_width()),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Set next timestamp").clicked() {
+                match self.timestamp_input.trim().parse::<u64>() {
+                    Ok(timestamp) => backend::set_next_block_timestamp(timestamp),
+                    Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
+                }
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.timestamp_input)
+                    .hint_text("unix timestamp")
+                    .desired_width(ui.available_width()),
+            );
+        });
+    }
+
+    fn render_mine_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Mine").clicked() {
+                let blocks = match self.mine_blocks_input.trim().is_empty() {
+                    true => Ok(1),
+                    false => self
+                        .mine_blocks_input
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|e| eyre::eyre!("Invalid block count: {}", e)),
+                };
+                let interval = match self.mine_interval_input.trim().is_empty() {
+                    true => Ok(1),
+                    false => utils::parse_duration_secs(&self.mine_interval_input),
+                };
+
+                match (blocks, interval) {
+                    (Ok(blocks), Ok(interval)) => backend::mine_blocks(blocks, interval),
+                    (Err(e), _) | (_, Err(e)) => backend::send_error_popup(format!("ERROR: {}", e)),
+                }
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.mine_blocks_input)
+                    .hint_text("1 block(s)")
+                    .desired_width(ui.available_width() * 0.5),
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut self.mine_interval_input)
+                    .hint_text("1s apart")
+                    .desired_width(ui.available_width()),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Roll to").clicked() {
+                match self.roll_input.trim().parse::<u64>() {
+                    Ok(block) => backend::roll_to_block(block),
+                    Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
+                }
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.roll_input)
+                    .hint_text("block number")
+                    .desired_width(ui.available_width()),
+            );
+        });
+    }
+}
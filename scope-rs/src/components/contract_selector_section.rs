@@ -5,6 +5,8 @@ use super::{SelectedTarget, TargetMode};
 #[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
 pub struct ContractSelectorSection {
     target: Option<SelectedTarget>,
+    search: String,
+    open_files_only: bool,
 }
 
 impl ContractSelectorSection {
@@ -16,23 +18,26 @@ impl ContractSelectorSection {
     fn render_contract_selector(&mut self, ui: &mut egui::Ui, render_configs: &mut RenderConfigs) {
         let selected_name = &mut render_configs.selected_name;
 
-        // Initialize a Vec to store tuples of the formatted name and filepath
-        let mut contract_list: Vec<(String, String)> = Vec::new();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.open_files_only, "open files only");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Search contracts")
+                    .desired_width(ui.available_width()),
+            );
+        });
 
-        for file_path in &(*STATE.open_files.read().unwrap()) {
-            if !file_path.is_empty() {
-                let split_path: Vec<&str> = file_path.split('/').collect();
-                let contract_name = split_path
-                    .last()
-                    .unwrap()
-                    .trim_end_matches(".json")
-                    .to_string();
-                let file_name = split_path[split_path.len() - 2].to_string();
-                let formatted_name = format!("{}:{}", file_name, contract_name);
-                contract_list.push((formatted_name, file_path.clone()));
-            }
-        }
-        contract_list.sort();
+        // (display name, artifact path) for every indexed contract matching the filters
+        let artifact_index = STATE.artifact_index.read().unwrap();
+        let open_files = STATE.open_files.read().unwrap();
+        let contract_list: Vec<(String, String)> = artifact_index
+            .search(
+                &self.search,
+                self.open_files_only.then_some(open_files.as_slice()),
+            )
+            .into_iter()
+            .map(|entry| (entry.display_name(), entry.artifact_path.clone()))
+            .collect();
         let prev_selected = selected_name.clone();
 
         egui::ComboBox::from_id_source("contract_selector")
@@ -71,18 +76,16 @@ impl ContractSelectorSection {
                             self.target = Some(SelectedTarget::new_load_raw());
                         }
                         _ => {
-                            if let Some((formatted_name, file_path)) =
-                                contract_list.iter().find(|(name, _)| name == new_selection)
-                            {
-                                if formatted_name.contains(".t.sol") {
-                                    self.target = Some(SelectedTarget::new_foundry_test(
-                                        file_path.to_string(),
-                                    ));
+                            if let Some(entry) = artifact_index.get_by_display_name(new_selection) {
+                                let file_path = entry.artifact_path.clone();
+                                if entry.is_test() {
+                                    self.target =
+                                        Some(SelectedTarget::new_foundry_test(file_path.clone()));
                                 } else {
                                     self.target =
-                                        Some(SelectedTarget::new_compiled(file_path.to_string()));
+                                        Some(SelectedTarget::new_compiled(file_path.clone()));
                                 }
-                                backend::query_for_compiled_solidity(file_path.to_string());
+                                backend::query_for_compiled_solidity(file_path);
                             }
                         }
                     }
@@ -118,6 +121,7 @@ impl ContractSelectorSection {
                     backend::query_for_compiled_solidity(file_path.to_string());
                 }
                 backend::query_for_open_files();
+                backend::query_for_artifact_index();
                 *STATE.completed_compile.write().unwrap() = Some(false);
             }
         }
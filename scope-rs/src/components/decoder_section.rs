@@ -0,0 +1,76 @@
+use crate::{backend, selectors, shared_state::STATE};
+
+// Decode calldata (and optionally what it returned) against every ABI we know of
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct DecoderSection {
+    calldata_input: String,
+    return_data_input: String,
+}
+
+impl DecoderSection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Decode Calldata", |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.calldata_input)
+                    .hint_text("Calldata")
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut self.return_data_input)
+                    .hint_text("Return data (optional)")
+                    .desired_width(f32::INFINITY),
+            );
+
+            let calldata_input = self.calldata_input.trim();
+            if calldata_input.is_empty() {
+                return;
+            }
+            // The artifacts' ABIs are only loaded once something needs them
+            backend::query_for_artifact_codes();
+            let Ok(calldata) = hex::decode(calldata_input.trim_start_matches("0x")) else {
+                ui.label("Calldata isn't valid hex");
+                return;
+            };
+
+            let registry = STATE.selector_registry.read().unwrap();
+            let decoded = match registry.decode_calldata(&calldata, None) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    ui.label(e.to_string());
+                    return;
+                }
+            };
+            let mut text = format!("{}\n{}", decoded.function.signature, decoded);
+            // Other contracts with the same function, e.g. ERC20 transfer
+            let others: Vec<String> = registry
+                .lookup(&format!("0x{}", hex::encode(&calldata[..4])))
+                .iter()
+                .filter(|function| function.contract_name != decoded.function.contract_name)
+                .map(|function| function.contract_name.clone())
+                .collect();
+            if !others.is_empty() {
+                text.push_str(&format!("\nalso in: {}", others.join(", ")));
+            }
+
+            let return_data_input = self.return_data_input.trim();
+            if !return_data_input.is_empty() {
+                let output = hex::decode(return_data_input.trim_start_matches("0x"))
+                    .map_err(|_| eyre::eyre!("Return data isn't valid hex"))
+                    .and_then(|return_data| {
+                        selectors::decode_output(&decoded.function.abi_item, &return_data)
+                    });
+                match output {
+                    Ok(output) => text.push_str(&format!("\nreturns: ({})", output)),
+                    Err(e) => text.push_str(&format!("\nreturns: {}", e)),
+                }
+            }
+
+            ui.add(
+                egui::TextEdit::multiline(&mut text.as_str())
+                    .desired_rows(1)
+                    .desired_width(f32::INFINITY),
+            );
+        });
+    }
+}
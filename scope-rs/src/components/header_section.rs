@@ -22,6 +22,7 @@ impl HeaderSection {
         ui.horizontal(|ui| {
             if ui.button("🔄").clicked() {
                 backend::query_for_open_files();
+                backend::query_for_artifact_index();
             }
             if ui.button("Compile").clicked() {
                 backend::send_forge_build();
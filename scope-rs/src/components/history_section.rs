@@ -0,0 +1,83 @@
+use crate::shared_state::STATE;
+use ethers::types::H160;
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct HistorySection {
+    contract_filter: Option<H160>, // None shows every contract
+}
+
+impl HistorySection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Transaction History", |ui| {
+            self.render_filter(ui);
+            self.render_records(ui);
+        });
+    }
+
+    fn render_filter(&mut self, ui: &mut egui::Ui) {
+        // Every contract that shows up in the history, in order of first appearance
+        let mut targets: Vec<(H160, String)> = Vec::new();
+        for record in STATE.tx_history.read().unwrap().iter() {
+            if !targets.iter().any(|(address, _)| *address == record.target) {
+                targets.push((record.target, record.target_name.clone()));
+            }
+        }
+
+        let selected_text = match self.contract_filter {
+            Some(address) => format!("{:#x}", address),
+            None => "All contracts".to_string(),
+        };
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("tx_history_filter")
+                .selected_text(selected_text)
+                .width(ui.available_width() * 0.75)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.contract_filter, None, "All contracts");
+                    for (address, name) in targets.iter() {
+                        ui.selectable_value(
+                            &mut self.contract_filter,
+                            Some(*address),
+                            format!("{} - {:#x}", name, address),
+                        );
+                    }
+                });
+
+            if ui.button("Clear").clicked() {
+                STATE.tx_history.write().unwrap().clear();
+                self.contract_filter = None;
+            }
+        });
+    }
+
+    fn render_records(&mut self, ui: &mut egui::Ui) {
+        // Reruns only push their record once the spawned future completes, so holding
+        // the read lock here is fine
+        let tx_history = STATE.tx_history.read().unwrap();
+
+        // Newest first
+        for (idx, record) in tx_history.iter().enumerate().rev() {
+            if self
+                .contract_filter
+                .is_some_and(|address| address != record.target)
+            {
+                continue;
+            }
+
+            ui.horizontal(|ui| {
+                ui.set_max_width(ui.available_width() - 60.0);
+                egui::CollapsingHeader::new(format!("#{} {}", idx, record.summary()))
+                    .id_source(("tx_record", idx))
+                    .show(ui, |ui| {
+                        record.show(ui);
+                    });
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                    if ui.button("Rerun").clicked() {
+                        record.rerun();
+                    }
+                });
+            });
+        }
+    }
+}
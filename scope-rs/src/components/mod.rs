@@ -1,16 +1,31 @@
 pub mod header_section;
 pub use header_section::HeaderSection;
+pub mod network_section;
+pub use network_section::NetworkSection;
 pub mod contract_selector_section;
 pub use contract_selector_section::ContractSelectorSection;
 pub mod tx_config_section;
 pub use tx_config_section::TxConfigSection;
+pub mod signer_section;
+pub use signer_section::SignerSection;
 pub mod deployed_section;
 pub use deployed_section::DeployedSection;
+pub mod history_section;
+pub use history_section::HistorySection;
+pub mod chain_section;
+pub use chain_section::ChainSection;
+pub mod snapshot_section;
+pub use snapshot_section::SnapshotSection;
+pub mod decoder_section;
+pub use decoder_section::DecoderSection;
+pub mod watch_section;
+pub use watch_section::WatchSection;
 
 pub mod sub_components;
 
 // Re-export sub_components
 pub use sub_components::{
-    AddressSelector, CompiledContract, CopyButton, DeployedContract, ReturnAndReceipt,
-    SelectedTarget, TargetMode, TestList, UtilityMenu,
+    AddressSelector, CompiledContract, CopyButton, DecodedLog, DeployedContract, DisassemblyView,
+    LogPage, ReturnAndReceipt, SelectedTarget, TargetMode, TestList, TxKind, TxRecord, TxReplay,
+    UtilityMenu, Watch,
 };
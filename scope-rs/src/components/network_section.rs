@@ -0,0 +1,197 @@
+use crate::{
+    backend,
+    shared_state::{self, ConnectionStatus, NetworkProfile, STATE},
+};
+use egui::Color32;
+
+// How often to check on the node, and retry connecting if it's down (ms)
+const CHECK_INTERVAL: f64 = 5000.0;
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct NetworkSection {
+    new_profile_name: String,
+
+    #[serde(skip)]
+    last_check: f64,
+}
+
+impl NetworkSection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.poll_connection();
+        self.render_selector(ui);
+        ui.collapsing("Networks", |ui| {
+            self.render_active_profile(ui);
+            ui.separator();
+            self.render_add_profile(ui);
+        });
+    }
+
+    // Lets the node be started (or restarted) after the webview without a reload
+    fn poll_connection(&mut self) {
+        let now = js_sys::Date::now();
+        if now - self.last_check < CHECK_INTERVAL {
+            return;
+        }
+        self.last_check = now;
+
+        let connection = STATE.connection.read().unwrap().clone();
+        match connection {
+            ConnectionStatus::Connected { .. } => backend::check_connection(),
+            ConnectionStatus::Failed(_) | ConnectionStatus::Disconnected => {
+                if let Some(profile) = shared_state::read_active_network() {
+                    backend::connect(profile);
+                }
+            }
+            ConnectionStatus::Connecting => {}
+        }
+    }
+
+    fn render_selector(&mut self, ui: &mut egui::Ui) {
+        let names: Vec<String> = STATE
+            .network_profiles
+            .read()
+            .unwrap()
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect();
+        let mut active_network = STATE.active_network.read().unwrap().clone();
+        let connection = STATE.connection.read().unwrap().clone();
+
+        ui.horizontal(|ui| {
+            ui.label("Network: ");
+            egui::ComboBox::from_id_source("network_profile")
+                .selected_text(active_network.clone())
+                .width(ui.available_width() * 0.4)
+                .show_ui(ui, |ui| {
+                    for name in names.iter() {
+                        ui.selectable_value(&mut active_network, name.clone(), name);
+                    }
+                });
+
+            let button_text = match connection {
+                ConnectionStatus::Connected { .. } => "Reconnect",
+                _ => "Connect",
+            };
+            let switched = active_network != *STATE.active_network.read().unwrap();
+            if switched {
+                *STATE.active_network.write().unwrap() = active_network;
+            }
+            if switched || ui.button(button_text).clicked() {
+                if let Some(profile) = shared_state::read_active_network() {
+                    backend::connect(profile);
+                }
+            }
+
+            let color = match connection {
+                ConnectionStatus::Connected { .. } => Color32::GREEN,
+                ConnectionStatus::Connecting => Color32::YELLOW,
+                ConnectionStatus::Failed(_) => Color32::RED,
+                ConnectionStatus::Disconnected => Color32::GRAY,
+            };
+            ui.colored_label(color, "●");
+            ui.label(connection.to_string());
+            if *STATE.live_updates.read().unwrap() {
+                ui.label("live");
+            }
+        });
+    }
+
+    // Edits to the url/chain id apply on the next connect
+    fn render_active_profile(&mut self, ui: &mut egui::Ui) {
+        let active_network = STATE.active_network.read().unwrap().clone();
+        let mut network_profiles = STATE.network_profiles.write().unwrap();
+        let profile_count = network_profiles.len();
+        let Some(profile_idx) = network_profiles
+            .iter()
+            .position(|profile| profile.name == active_network)
+        else {
+            return;
+        };
+        let profile = &mut network_profiles[profile_idx];
+
+        ui.horizontal(|ui| {
+            ui.label("Url:      ");
+            ui.add(
+                egui::TextEdit::singleline(&mut profile.url)
+                    .hint_text("http://127.0.0.1:8545")
+                    .desired_width(ui.available_width()),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Ws url:   ");
+            ui.add(
+                egui::TextEdit::singleline(&mut profile.ws_url)
+                    .hint_text("optional, for live updates (ws://127.0.0.1:8545)")
+                    .desired_width(ui.available_width()),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Chain id: ");
+            ui.add(
+                egui::TextEdit::singleline(&mut profile.chain_id)
+                    .hint_text("any")
+                    .desired_width(ui.available_width()),
+            );
+        });
+
+        // Used on connect, if saved
+        ui.label(format!(
+            "From: {}  Tx configs: {}",
+            profile
+                .signer
+                .map_or("-".to_string(), |signer| format!("{:#x}", signer)),
+            if profile.tx_configs.is_some() {
+                "saved"
+            } else {
+                "-"
+            }
+        ));
+
+        let mut should_remove = false;
+        ui.horizontal(|ui| {
+            if ui.button("Save current configs").clicked() {
+                let tx_configs = shared_state::read_tx_configs();
+                profile.signer = Some(tx_configs.from_address);
+                profile.tx_configs = Some(tx_configs);
+            }
+            if ui.button("Clear configs").clicked() {
+                profile.signer = None;
+                profile.tx_configs = None;
+            }
+            should_remove = profile_count > 1 && ui.button("Remove network").clicked();
+        });
+
+        if should_remove {
+            network_profiles.remove(profile_idx);
+            *STATE.active_network.write().unwrap() = network_profiles[0].name.clone();
+            backend::connect(network_profiles[0].clone());
+        }
+    }
+
+    fn render_add_profile(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Add network").clicked() {
+                let name = self.new_profile_name.trim().to_string();
+                let mut network_profiles = STATE.network_profiles.write().unwrap();
+                if name.is_empty() || network_profiles.iter().any(|p| p.name == name) {
+                    backend::send_error_popup("Network names must be unique".to_string());
+                } else {
+                    let profile = NetworkProfile {
+                        name: name.clone(),
+                        ..Default::default()
+                    };
+                    network_profiles.push(profile.clone());
+                    *STATE.active_network.write().unwrap() = name;
+                    self.new_profile_name.clear();
+                    // Same as switching to it, so the status is for this profile
+                    backend::connect(profile);
+                }
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_profile_name)
+                    .hint_text("name")
+                    .desired_width(ui.available_width()),
+            );
+        });
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    backend,
+    shared_state::STATE,
+    signers::{self, AccountMode},
+};
+use ethers::signers::LocalWallet;
+use eyre::Result;
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+enum SignerSource {
+    #[default]
+    Mnemonic,
+    PrivateKey,
+    Keystore,
+}
+
+impl std::fmt::Display for SignerSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignerSource::Mnemonic => write!(f, "Mnemonic"),
+            SignerSource::PrivateKey => write!(f, "Private key"),
+            SignerSource::Keystore => write!(f, "Keystore"),
+        }
+    }
+}
+
+// Secrets are never persisted, and are cleared once imported
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct SignerSection {
+    source: SignerSource,
+    derivation_path: String,
+    start_index: String,
+    count: String,
+
+    #[serde(skip)]
+    mnemonic: String,
+    #[serde(skip)]
+    private_key: String,
+    #[serde(skip)]
+    keystore_json: String,
+    #[serde(skip)]
+    keystore_password: String,
+}
+
+impl SignerSection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Signers", |ui| {
+            self.render_import(ui);
+            ui.separator();
+            render_accounts(ui);
+        });
+    }
+
+    fn render_import(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Source: ");
+            egui::ComboBox::from_id_source("signer_source")
+                .selected_text(self.source.to_string())
+                .width(ui.available_width() * 0.5)
+                .show_ui(ui, |ui| {
+                    for source in [
+                        SignerSource::Mnemonic,
+                        SignerSource::PrivateKey,
+                        SignerSource::Keystore,
+                    ] {
+                        ui.selectable_value(&mut self.source, source, source.to_string());
+                    }
+                });
+        });
+
+        match self.source {
+            SignerSource::Mnemonic => {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.mnemonic)
+                        .hint_text("mnemonic")
+                        .password(true)
+                        .desired_width(f32::INFINITY),
+                );
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.derivation_path)
+                            .hint_text(signers::DEFAULT_DERIVATION_PATH)
+                            .desired_width(ui.available_width() * 0.5),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.start_index)
+                            .hint_text("from index (0)")
+                            .desired_width(ui.available_width() * 0.5),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.count)
+                            .hint_text(format!("count (1, max {})", signers::MAX_MNEMONIC_ACCOUNTS))
+                            .desired_width(ui.available_width()),
+                    );
+                });
+            }
+            SignerSource::PrivateKey => {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.private_key)
+                        .hint_text("private key")
+                        .password(true)
+                        .desired_width(f32::INFINITY),
+                );
+            }
+            SignerSource::Keystore => {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.keystore_json)
+                        .hint_text("keystore json")
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.keystore_password)
+                        .hint_text("password")
+                        .password(true)
+                        .desired_width(f32::INFINITY),
+                );
+            }
+        }
+
+        if *STATE.decrypting_keystore.read().unwrap() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Decrypting keystore...");
+            });
+            return;
+        }
+
+        if ui.button("Import").clicked() {
+            ui.ctx().request_repaint();
+            match self.source {
+                // Handed off as-is, so the inputs are cleared whether or not it decrypts
+                SignerSource::Keystore => signers::decrypt_keystore(
+                    std::mem::take(&mut self.keystore_json),
+                    std::mem::take(&mut self.keystore_password),
+                    |res| match res {
+                        Ok(wallet) => import_wallets(vec![wallet]),
+                        Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
+                    },
+                ),
+                _ => match self.wallets_from_inputs() {
+                    Ok(wallets) => {
+                        import_wallets(wallets);
+                        self.mnemonic.clear();
+                        self.private_key.clear();
+                    }
+                    Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
+                },
+            }
+        }
+    }
+
+    fn wallets_from_inputs(&self) -> Result<Vec<LocalWallet>> {
+        match self.source {
+            SignerSource::Mnemonic => signers::wallets_from_mnemonic(
+                &self.mnemonic,
+                &self.derivation_path,
+                parse_or(&self.start_index, 0)?,
+                parse_or(&self.count, 1)?,
+            ),
+            SignerSource::PrivateKey => {
+                Ok(vec![signers::wallet_from_private_key(&self.private_key)?])
+            }
+            SignerSource::Keystore => unreachable!("keystores go through decrypt_keystore"),
+        }
+    }
+}
+
+fn import_wallets(wallets: Vec<LocalWallet>) {
+    let added = signers::add_wallets(wallets);
+    // Select the first imported account so the next tx is signed with it
+    if let Some(address) = added.first() {
+        STATE.tx_configs.write().unwrap().from_address = *address;
+    }
+    backend::send_ok_popup(format!("Imported {} account(s)", added.len()));
+}
+
+// Every "from" account and how it signs, with a toggle for the mode where it makes sense
+fn render_accounts(ui: &mut egui::Ui) {
+    let from_addresses = STATE.from_addresses.read().unwrap().clone();
+    for address in from_addresses {
+        ui.horizontal(|ui| {
+            let mode = signers::account_mode(address);
+            ui.label(format!("{:#x} ({})", address, mode));
+            ui.with_layout(
+                egui::Layout::right_to_left(egui::Align::Min),
+                |ui| match mode {
+                    AccountMode::Local => {
+                        if ui.button("Forget key").clicked() {
+                            STATE.signers.write().unwrap().remove(&address);
+                        }
+                    }
+                    AccountMode::Impersonated => {
+                        if ui.button("Stop impersonating").clicked() {
+                            backend::stop_impersonating_account(address);
+                        }
+                    }
+                    AccountMode::Node => {
+                        if ui.button("Impersonate").clicked() {
+                            backend::impersonate_account(address);
+                        }
+                    }
+                },
+            );
+        });
+    }
+}
+
+fn parse_or(input: &str, default: u32) -> Result<u32> {
+    match input.trim() {
+        "" => Ok(default),
+        input => Ok(input.parse()?),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{backend, shared_state::STATE};
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct SnapshotSection {
+    label_input: String,
+}
+
+impl SnapshotSection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Snapshots", |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Snapshot").clicked() {
+                    let label = match self.label_input.trim().is_empty() {
+                        true => format!("snapshot {}", STATE.snapshots.read().unwrap().len()),
+                        false => self.label_input.trim().to_string(),
+                    };
+                    backend::take_snapshot(label);
+                    self.label_input.clear();
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.label_input)
+                        .hint_text("label")
+                        .desired_width(ui.available_width()),
+                );
+            });
+
+            // Newest first; reverting to one also drops everything after it
+            for snapshot in STATE.snapshots.read().unwrap().iter().rev() {
+                ui.horizontal(|ui| {
+                    if ui.button("Revert").clicked() {
+                        backend::revert_to_snapshot(snapshot.clone());
+                    }
+                    ui.label(format!(
+                        "{} - block {} - {}",
+                        snapshot.label, snapshot.block_number, snapshot.taken_at
+                    ));
+                });
+            }
+        });
+    }
+}
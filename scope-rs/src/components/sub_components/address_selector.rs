@@ -1,7 +1,7 @@
 use egui::Ui;
 use ethers::types::{Address, U256};
 
-use crate::{app::RenderConfigs, backend, shared_state::STATE};
+use crate::{app::RenderConfigs, backend, shared_state::STATE, signers};
 use eyre::Result;
 
 use super::CopyButton;
@@ -10,6 +10,7 @@ use super::CopyButton;
 pub struct AddressSelector {
     pub new_address_input: String,
     pub new_balance_input: String,
+    pub new_nonce_input: String,
 }
 
 impl AddressSelector {
@@ -25,6 +26,9 @@ impl AddressSelector {
         let show_balance_input = &mut render_configs.show_balance_input;
         let new_balance_input = &mut self.new_balance_input;
 
+        let show_nonce_input = &mut render_configs.show_nonce_input;
+        let new_nonce_input = &mut self.new_nonce_input;
+
         // Existing dropdown
         ui.horizontal(|ui| {
             ui.label("From:  ");
@@ -34,7 +38,11 @@ impl AddressSelector {
                 .wrap(false)
                 .show_ui(ui, |ui| {
                     for address in from_addresses.iter() {
-                        ui.selectable_value(from_address, *address, format!("{}", address));
+                        ui.selectable_value(
+                            from_address,
+                            *address,
+                            format!("{} ({})", address, signers::account_mode(*address)),
+                        );
                     }
                     ui.selectable_value(
                         show_new_address_input,
@@ -46,6 +54,9 @@ impl AddressSelector {
             let full_address = format!("{:#x}", from_address);
             // Button to toggle the visibility of the text input for a new address
             CopyButton::new("📋".to_string(), full_address.clone()).show(ui);
+            if let Some(balance) = STATE.balances.read().unwrap().get(from_address) {
+                ui.label(format!("{} ether", ethers::utils::format_ether(*balance)));
+            }
             if ui.button("Get Bal.").clicked() {
                 backend::send_shell_command(format!("cast balance {}", full_address));
             }
@@ -53,6 +64,14 @@ impl AddressSelector {
             if ui.button("Set").clicked() {
                 *show_balance_input = !(*show_balance_input);
             }
+
+            if ui.button("Nonce").clicked() {
+                backend::get_nonce(*from_address);
+            }
+
+            if ui.button("Set Nonce").clicked() {
+                *show_nonce_input = !(*show_nonce_input);
+            }
         });
         if *show_balance_input {
             ui.horizontal(|ui| {
@@ -78,6 +97,26 @@ impl AddressSelector {
             });
         }
 
+        if *show_nonce_input {
+            ui.horizontal(|ui| {
+                ui.label("New Nonce:");
+
+                ui.add(
+                    egui::TextEdit::singleline(new_nonce_input)
+                        .desired_width(ui.available_width() * 0.5),
+                );
+                if ui.button("Confirm").clicked() {
+                    if let Ok(nonce) = new_nonce_input.trim().parse::<u64>() {
+                        backend::set_nonce(*from_address, U256::from(nonce));
+                        *show_nonce_input = false;
+                        new_nonce_input.clear();
+                    } else {
+                        backend::send_error_popup("Failed to parse nonce".to_string());
+                    }
+                }
+            });
+        }
+
         if *show_new_address_input {
             let new_address_input = &mut self.new_address_input;
             // Text input for a new address
@@ -93,6 +132,8 @@ impl AddressSelector {
 
                     match ethers_address {
                         Ok(address) => {
+                            // New addresses send via impersonation, see the Signers section
+                            backend::impersonate_account(address);
                             from_addresses.push(address);
                             new_address_input.clear();
                             *show_new_address_input = false;
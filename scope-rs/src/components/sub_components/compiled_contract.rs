@@ -1,4 +1,8 @@
-use crate::{backend, components::CopyButton, shared_state::STATE};
+use crate::{
+    backend, bytecode,
+    components::{CopyButton, DisassemblyView},
+    shared_state::{self, STATE},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -11,9 +15,18 @@ pub struct CompiledContract {
     // pub bytecode_b: Vec<u8>,
     pub bytecode_string: String,
     pub compiled_json: Value,
+    #[serde(skip)]
+    pub disassembly: Option<DisassemblyView>, // built the first time it's opened
 }
 
 impl CompiledContract {
+    // 0x-prefixed runtime code, e.g. for etching over an existing address
+    pub fn deployed_bytecode(&self) -> Option<String> {
+        self.compiled_json["deployedBytecode"]["object"]
+            .as_str()
+            .map(|code| format!("0x{}", code.trim_start_matches("0x")))
+    }
+
     pub fn new(file_path: String, compiled_json: Value) -> Self {
         let split_path: Vec<&str> = file_path.split('/').collect();
         let contract_name = split_path
@@ -38,6 +51,26 @@ impl CompiledContract {
             abi,
             bytecode_string,
             compiled_json,
+            disassembly: None,
+        }
+    }
+
+    // Runtime + creation code listings
+    pub fn show_disassembly(&mut self, ui: &mut egui::Ui) {
+        if self.disassembly.is_none() {
+            let runtime = bytecode::decode_unlinked(&self.compiled_json["deployedBytecode"])
+                .unwrap_or_default();
+            let creation =
+                bytecode::decode_unlinked(&self.compiled_json["bytecode"]).unwrap_or_default();
+            let mut abis = vec![self.abi.clone()];
+            abis.extend(shared_state::read_artifact_abis());
+            self.disassembly = Some(DisassemblyView::new(
+                &[("Runtime", &runtime), ("Creation", &creation)],
+                &abis,
+            ));
+        }
+        if let Some(disassembly) = self.disassembly.as_mut() {
+            disassembly.show(ui, &self.file_path);
         }
     }
 }
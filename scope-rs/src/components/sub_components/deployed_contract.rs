@@ -1,8 +1,13 @@
 // use eth_toolkit::shared_storage::{DeployedContract, BRIDGE};
 use crate::{
     abi, backend,
-    components::{CompiledContract, UtilityMenu},
-    shared_state::{self, STATE},
+    bytecode::BytecodeMatch,
+    components::{CompiledContract, DisassemblyView, TxKind, TxRecord, TxReplay, UtilityMenu},
+    disassembler::{self, DispatcherSelector},
+    proxy::ProxyInfo,
+    selectors::{self, DecodedCall},
+    shared_state::{self, TxConfigs, STATE},
+    storage::{self, StorageItem, StorageLayout},
     utils,
 };
 use egui::{
@@ -19,13 +24,18 @@ use ethers::{
     abi::Token,
     providers::Middleware,
     types::{
-        transaction::eip2718::TypedTransaction, Block, Bytes, Eip1559TransactionRequest, H160,
-        H256, U256,
+        transaction::eip2718::TypedTransaction, Block, Bytes, Eip1559TransactionRequest, Log, H160,
+        H256, U64,
     },
 };
 
 // use ethers::contract::Contract;
 
+// Function outputs are keyed by selector, so this can't collide with a real function
+const RAW_CALLDATA_KEY: &str = "raw_calldata";
+// The raw call's result keeps the calldata it was sent with under this input
+const RAW_CALLDATA_INPUT: &str = "calldata";
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct DeployedContract {
     pub name: String,
@@ -37,10 +47,43 @@ pub struct DeployedContract {
     pub deployed_block: Block<H256>,
     pub storage_slot_input: String,
     pub storage_value_input: String,
-    pub func_param_input: HashMap<String, HashMap<String, String>>, //func_name -> param name -> param input;
-    pub func_output: HashMap<String, ReturnAndReceipt>,
+    pub storage_key_type_input: String, // for stepping the slot input into a mapping
+    pub storage_key_input: String,
+    pub slot_calc_variable: Option<String>, // label of the variable to index into
+    pub slot_calc_keys: Vec<String>,        // one per mapping/array/struct level
+    pub slot_calc_value_input: String,
+    pub storage_values: HashMap<String, String>, // variable -> decoded value, from the storage layout
+    pub storage_inputs: HashMap<String, String>, // variable -> typed value to write
+    pub set_code_input: String,                  // raw runtime code to etch
+    pub set_code_artifact: Option<String>,       // artifact to etch instead, loaded once picked
+    pub nonce_input: String,
+    pub func_param_input: HashMap<String, HashMap<String, String>>, // selector -> param name -> param input;
+    pub func_output: HashMap<String, ReturnAndReceipt>, // selector -> most recent output
+    pub show_state_table: bool,
     pub calldata_input: String,
     pub compiled_contract: Option<CompiledContract>,
+    pub proxy: Option<ProxyInfo>,                // detected on load
+    pub bytecode_matches: Vec<BytecodeMatch>, // partial matches from the artifacts, when loaded without an ABI
+    pub proxy_abi: Option<Value>, // the proxy's own ABI, while an implementation's is attached
+    pub implementation_name: Option<String>, // contract whose ABI is attached
+    pub implementation_artifact: Option<String>, // artifact whose ABI to attach, loaded once picked
+    pub event_filter: Option<String>, // event signature to filter historical logs by
+    #[serde(skip)]
+    pub disassembly: Option<DisassemblyView>, // built the first time it's opened
+    #[serde(skip)]
+    pub storage_layout: Option<StorageLayout>, // parsed once found (the build-info may load later)
+    #[serde(skip)]
+    pub dispatcher_selectors: Option<Vec<DispatcherSelector>>, // raw calldata presets
+    #[serde(skip)]
+    pub decoded_calldata: Option<(String, u64, String)>, // (input, registry generation, decoded)
+    #[serde(skip)]
+    pub raw_output_text: Option<String>, // the raw call's result, decoded once it comes in
+    #[serde(skip)]
+    pub state_rows: Option<Vec<(String, String)>>, // (view, decoded value) for the state table
+    pub receipt_logs: Vec<DecodedLog>,
+    pub historical_logs: Vec<DecodedLog>,
+    #[serde(default)]
+    pub history_from_block: Option<u64>, // oldest block the historical logs were searched from
 
     // this is def not how we want to do this but fine for mvp
     pub func_last_tx: HashMap<String, ReturnAndReceipt>, // func name: most recent tx?
@@ -48,12 +91,187 @@ pub struct DeployedContract {
 
 impl DeployedContract {
     pub fn show(&mut self, ui: &mut Ui) {
+        if let Some(balance) = STATE.balances.read().unwrap().get(&self.address_h160) {
+            ui.label(format!(
+                "Balance: {} ether",
+                ethers::utils::format_ether(*balance)
+            ));
+        }
+        self.show_bytecode_matches(ui);
+        self.show_proxy(ui);
         self.show_storage(ui);
+        self.show_set_code(ui);
+        self.show_state(ui);
         self.show_functions_and_inputs(ui);
         self.show_calldata_area(ui);
+        self.show_events(ui);
+        self.show_disassembly(ui);
+    }
+
+    // Candidates for code that didn't exactly match any artifact, e.g. built with other settings
+    fn show_bytecode_matches(&mut self, ui: &mut Ui) {
+        if let Some(compiled) = STATE
+            .temp_matched_artifacts
+            .write()
+            .unwrap()
+            .remove(&format!("{:#x}", self.address_h160))
+        {
+            self.use_compiled(compiled);
+        }
+        if self.bytecode_matches.is_empty() {
+            return;
+        }
+
+        ui.label("Possible matches:");
+        for bytecode_match in self.bytecode_matches.iter() {
+            ui.horizontal(|ui| {
+                if ui.button("Use").clicked() {
+                    backend::use_bytecode_match(
+                        self.address_h160,
+                        bytecode_match.artifact_path.clone(),
+                    );
+                }
+                ui.label(format!(
+                    "{} ({:.0}% similar)",
+                    bytecode_match.display_name,
+                    bytecode_match.similarity * 100.0
+                ));
+            });
+        }
+    }
+
+    // Everything derived from the old artifact goes too, and is rebuilt from this one
+    fn use_compiled(&mut self, compiled: CompiledContract) {
+        self.name = compiled.contract_name.clone();
+        self.abi = compiled.abi.clone();
+        self.compiled_contract = Some(compiled);
+        self.bytecode_matches.clear();
+        self.storage_layout = None;
+        self.storage_values.clear();
+        self.slot_calc_variable = None;
+        self.slot_calc_keys.clear();
+        self.dispatcher_selectors = None;
+        self.disassembly = None;
+        self.decoded_calldata = None;
+        self.raw_output_text = None;
+        self.state_rows = None;
+    }
+
+    // Calls still go to this address, just with the implementation's interface on top of the
+    // proxy's own (which is shown separately)
+    fn show_proxy(&mut self, ui: &mut Ui) {
+        let Some(proxy) = self.proxy.clone() else {
+            return;
+        };
+
+        ui.label(format!("Proxy ({})", proxy.kind));
+        ui.label(format!("Implementation: {:#x}", proxy.implementation));
+        if let Some(beacon) = proxy.beacon {
+            ui.label(format!("Beacon: {:#x}", beacon));
+        }
+        if let Some(admin) = proxy.admin {
+            ui.label(format!("Admin: {:#x}", admin));
+        }
+
+        if let Some(implementation_name) = self.implementation_name.clone() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Using {} ABI", implementation_name));
+                if ui.button("Detach").clicked() {
+                    self.detach_implementation();
+                }
+            });
+            return;
+        }
+
+        let artifact_names = artifact_names();
+        let implementation = picked_artifact(&self.implementation_artifact);
+        ui.horizontal(|ui| {
+            if ui.button("Attach ABI").clicked() {
+                match (&implementation, self.implementation_artifact.clone()) {
+                    (Some(compiled), _) => self.attach_implementation(compiled),
+                    (None, Some(artifact_path)) => still_loading(&artifact_path),
+                    (None, None) => backend::send_error_popup(
+                        "Select the implementation's contract first".to_string(),
+                    ),
+                }
+            }
+            let prev_selected = self.implementation_artifact.clone();
+            egui::ComboBox::from_id_source(("implementation_artifact", self.address_h160))
+                .selected_text(
+                    self.implementation_artifact
+                        .as_ref()
+                        .and_then(|file_path| {
+                            artifact_names.iter().find(|(path, _)| path == file_path)
+                        })
+                        .map_or("Implementation".to_string(), |(_, name)| name.clone()),
+                )
+                .show_ui(ui, |ui| {
+                    for (file_path, contract_name) in artifact_names.iter() {
+                        ui.selectable_value(
+                            &mut self.implementation_artifact,
+                            Some(file_path.clone()),
+                            contract_name.as_str(),
+                        );
+                    }
+                });
+            if self.implementation_artifact != prev_selected {
+                if let Some(artifact_path) = self.implementation_artifact.clone() {
+                    backend::query_for_artifact(artifact_path);
+                }
+            }
+        });
+    }
+
+    // The implementation's functions plus any of the proxy's that it doesn't also have
+    pub fn attach_implementation(&mut self, compiled: &CompiledContract) {
+        let proxy_abi = self.proxy_abi.take().unwrap_or_else(|| self.abi.clone());
+        let mut abi_items = compiled.abi.as_array().cloned().unwrap_or_default();
+        let implementation_selectors: Vec<String> = abi_items
+            .iter()
+            .filter(|abi_item| abi_item["type"] == "function")
+            .map(abi::function_selector)
+            .collect();
+        abi_items.extend(
+            proxy_abi
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|abi_item| {
+                    abi_item["type"] != "function"
+                        || !implementation_selectors.contains(&abi::function_selector(abi_item))
+                }),
+        );
+
+        self.abi = Value::Array(abi_items);
+        self.proxy_abi = Some(proxy_abi);
+        self.implementation_name = Some(compiled.contract_name.clone());
+        // decoded against the old ABI
+        self.decoded_calldata = None;
+        self.raw_output_text = None;
+        self.state_rows = None;
+    }
+
+    pub fn detach_implementation(&mut self) {
+        if let Some(proxy_abi) = self.proxy_abi.take() {
+            self.abi = proxy_abi;
+        }
+        self.implementation_name = None;
+        self.decoded_calldata = None;
+        self.raw_output_text = None;
+        self.state_rows = None;
     }
 
     fn show_storage(&mut self, ui: &mut Ui) {
+        if let Some(values) = STATE
+            .temp_storage_values
+            .write()
+            .unwrap()
+            .remove(&format!("{:#x}", self.address_h160))
+        {
+            self.storage_values.extend(values);
+        }
+
         ui.label("Storage");
         let slot: &mut String = &mut self.storage_slot_input;
         let value: &mut String = &mut self.storage_value_input;
@@ -106,231 +324,683 @@ impl DeployedContract {
                 .desired_width(ui.available_width())
                 .show(ui);
         });
+
+        // Steps the slot above into a mapping, like `cast index`
+        ui.horizontal_top(|ui| {
+            if ui.button("Index").clicked() {
+                let (radix, clean_slot) = utils::get_radix_and_clean_str(slot);
+                let key_type = match self.storage_key_type_input.trim() {
+                    "" => "uint256",
+                    key_type => key_type,
+                };
+                let res = ethers::types::U256::from_str_radix(clean_slot, radix)
+                    .map_err(|e| eyre!("Invalid slot: {}", e))
+                    .and_then(|mapping_slot| {
+                        storage::mapping_slot(key_type, &self.storage_key_input, mapping_slot)
+                    });
+                match res {
+                    Ok(entry_slot) => *slot = format!("{:#x}", entry_slot),
+                    Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
+                }
+            }
+            egui::TextEdit::singleline(&mut self.storage_key_type_input)
+                .hint_text("key type (uint256)")
+                .desired_width(ui.available_width() * 0.3)
+                .show(ui);
+            egui::TextEdit::singleline(&mut self.storage_key_input)
+                .hint_text("key")
+                .desired_width(ui.available_width())
+                .show(ui);
+        });
+
+        // Taken out while drawing so the sections below can still borrow self mutably
+        let layout = self.storage_layout.take().or_else(|| {
+            self.compiled_contract
+                .as_ref()
+                .and_then(StorageLayout::for_compiled)
+        });
+        if let Some(layout) = &layout {
+            ui.collapsing("Slot calculator", |ui| {
+                self.show_slot_calculator(ui, layout);
+            });
+        }
+        ui.collapsing("Layout", |ui| {
+            match &layout {
+                Some(layout) => self.show_storage_layout(ui, layout),
+                None => {
+                    ui.label("No storage layout, add extra_output = [\"storageLayout\"] to foundry.toml and rebuild");
+                }
+            }
+        });
+        self.storage_layout = layout;
     }
 
-    // TODO: clean this way tf up
-    fn show_functions_and_inputs(&mut self, ui: &mut Ui) {
-        if let Some(abi_array) = self.abi.as_array() {
-            // Collect functions into a Vec
+    // Picks a mapping/array/struct variable and walks it down with one key per level, then
+    // reads or writes whatever's at the resulting slot
+    fn show_slot_calculator(&mut self, ui: &mut Ui, layout: &StorageLayout) {
+        let indexable: Vec<&StorageItem> = layout
+            .storage
+            .iter()
+            .filter(|item| storage::key_hint(layout, &item.type_id).is_some())
+            .collect();
+
+        let previous_variable = self.slot_calc_variable.clone();
+        egui::ComboBox::from_id_source(("slot_calc_variable", self.address_h160))
+            .selected_text(
+                self.slot_calc_variable
+                    .clone()
+                    .unwrap_or_else(|| "Variable".to_string()),
+            )
+            .width(ui.available_width() * 0.6)
+            .show_ui(ui, |ui| {
+                for item in indexable.iter() {
+                    ui.selectable_value(
+                        &mut self.slot_calc_variable,
+                        Some(item.label.clone()),
+                        format!("{} ({})", item.label, layout.type_label(&item.type_id)),
+                    );
+                }
+            });
+        if self.slot_calc_variable != previous_variable {
+            self.slot_calc_keys.clear();
+        }
 
-            let mut functions: Vec<&serde_json::Value> = abi_array
-                .iter()
-                .filter(|abi_item| {
-                    if let Some(abi_type) = abi_item.get("type") {
-                        abi_type == "function"
-                    } else {
-                        false
+        let Some(item) = indexable
+            .iter()
+            .find(|item| Some(&item.label) == self.slot_calc_variable.as_ref())
+        else {
+            return;
+        };
+
+        // An input for each level, until a key is missing or we hit a value
+        let mut type_id = item.type_id.clone();
+        let mut levels = 0;
+        while let Some(hint) = storage::key_hint(layout, &type_id) {
+            if self.slot_calc_keys.len() <= levels {
+                self.slot_calc_keys.push(String::new());
+            }
+            egui::TextEdit::singleline(&mut self.slot_calc_keys[levels])
+                .hint_text(hint)
+                .desired_width(f32::INFINITY)
+                .show(ui);
+            levels += 1;
+
+            match storage::resolve_path(layout, item, &self.slot_calc_keys[..levels]) {
+                Ok(target) if !self.slot_calc_keys[levels - 1].trim().is_empty() => {
+                    type_id = target.type_id
+                }
+                _ => break,
+            }
+        }
+        self.slot_calc_keys.truncate(levels);
+
+        let keys: Vec<String> = self
+            .slot_calc_keys
+            .iter()
+            .take_while(|key| !key.trim().is_empty())
+            .cloned()
+            .collect();
+        let target = match storage::resolve_path(layout, item, &keys) {
+            Ok(target) => target,
+            Err(e) => {
+                ui.label(e.to_string());
+                return;
+            }
+        };
+        let target_slot = target.slot().unwrap_or_default();
+
+        ui.label(format!(
+            "{} ({}): slot {:#x} offset {}",
+            target.label,
+            layout.type_label(&target.type_id),
+            target_slot,
+            target.offset
+        ));
+        ui.horizontal(|ui| {
+            if ui.button("Read").clicked() {
+                storage::read_items(self.address_h160, layout.clone(), vec![target.clone()]);
+            }
+            // For the raw Get/Set above
+            if ui.button("Use slot").clicked() {
+                self.storage_slot_input = format!("{:#x}", target_slot);
+            }
+        });
+        if let Some(value) = self.storage_values.get(&target.label) {
+            ui.add(
+                egui::TextEdit::multiline(&mut value.as_str())
+                    .desired_rows(1)
+                    .desired_width(f32::INFINITY),
+            );
+        }
+
+        let is_writable = layout
+            .type_of(&target.type_id)
+            .is_ok_and(|storage_type| storage_type.is_writable());
+        if is_writable {
+            ui.horizontal_top(|ui| {
+                if ui.button("Set").clicked() {
+                    storage::write(
+                        self.address_h160,
+                        layout.clone(),
+                        target.clone(),
+                        self.slot_calc_value_input.clone(),
+                    );
+                }
+                egui::TextEdit::singleline(&mut self.slot_calc_value_input)
+                    .hint_text(layout.type_label(&target.type_id))
+                    .desired_width(ui.available_width())
+                    .show(ui);
+            });
+        }
+    }
+
+    // Every state variable with its slot/offset/type, read and decoded from storage. Value
+    // types, strings and bytes can be written back.
+    fn show_storage_layout(&mut self, ui: &mut Ui, layout: &StorageLayout) {
+        if ui.button("Read").clicked() {
+            storage::read_all(self.address_h160, layout.clone());
+        }
+
+        for item in layout.storage.iter() {
+            ui.separator();
+            let type_label = layout.type_label(&item.type_id);
+            ui.horizontal(|ui| {
+                ui.label(format!("{} ({})", item.label, type_label));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                    ui.label(format!("slot {} offset {}", item.slot, item.offset));
+                });
+            });
+
+            if let Some(value) = self.storage_values.get(&item.label) {
+                ui.add(
+                    egui::TextEdit::multiline(&mut value.as_str())
+                        .desired_rows(1)
+                        .desired_width(f32::INFINITY),
+                );
+            }
+
+            let is_writable = layout
+                .type_of(&item.type_id)
+                .is_ok_and(|storage_type| storage_type.is_writable());
+            if is_writable {
+                let input = self.storage_inputs.entry(item.label.clone()).or_default();
+                ui.horizontal_top(|ui| {
+                    if ui.button("Set").clicked() {
+                        storage::write(
+                            self.address_h160,
+                            layout.clone(),
+                            item.clone(),
+                            input.clone(),
+                        );
                     }
-                })
+                    egui::TextEdit::singleline(input)
+                        .hint_text(type_label)
+                        .desired_width(ui.available_width())
+                        .show(ui);
+                });
+            }
+        }
+    }
+
+    // Etches over the code at this address, either a compiled contract's runtime code (whose ABI
+    // the contract is then reloaded with) or raw hex (keeping the current ABI)
+    fn show_set_code(&mut self, ui: &mut Ui) {
+        ui.label("Set code");
+        let artifact_names = artifact_names();
+        let picked = picked_artifact(&self.set_code_artifact);
+        let selected_text = self
+            .set_code_artifact
+            .as_ref()
+            .and_then(|file_path| artifact_names.iter().find(|(path, _)| path == file_path))
+            .map_or("Raw bytecode".to_string(), |(_, name)| name.clone());
+
+        ui.horizontal_top(|ui| {
+            if ui.button("Etch").clicked() {
+                match (picked, &self.set_code_artifact) {
+                    (Some(compiled), _) => match compiled.deployed_bytecode() {
+                        Some(code) => backend::set_code_wrapper(
+                            self.address_string.clone(),
+                            code,
+                            Some(compiled),
+                        ),
+                        None => backend::send_error_popup(
+                            "ERROR: No deployedBytecode in artifact".to_string(),
+                        ),
+                    },
+                    (None, Some(artifact_path)) => still_loading(artifact_path),
+                    (None, None) if self.set_code_input.is_empty() => {
+                        backend::send_error_popup("Empty bytecode".to_string())
+                    }
+                    (None, None) => backend::set_code_wrapper(
+                        self.address_string.clone(),
+                        self.set_code_input.clone(),
+                        self.compiled_contract.clone(),
+                    ),
+                }
+            }
+
+            let prev_selected = self.set_code_artifact.clone();
+            egui::ComboBox::from_id_source(("set_code_artifact", self.address_h160))
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.set_code_artifact, None, "Raw bytecode");
+                    for (file_path, contract_name) in artifact_names.iter() {
+                        ui.selectable_value(
+                            &mut self.set_code_artifact,
+                            Some(file_path.clone()),
+                            contract_name.as_str(),
+                        );
+                    }
+                });
+            if self.set_code_artifact != prev_selected {
+                if let Some(artifact_path) = self.set_code_artifact.clone() {
+                    backend::query_for_artifact(artifact_path);
+                }
+            }
+
+            if self.set_code_artifact.is_none() {
+                egui::TextEdit::singleline(&mut self.set_code_input)
+                    .hint_text("0x runtime code")
+                    .desired_width(ui.available_width())
+                    .show(ui);
+            }
+        });
+    }
+
+    // Every parameterless view, read in one go and shown as a table. The outputs land in
+    // func_output like any other call, so the function rows below update too.
+    fn show_state(&mut self, ui: &mut Ui) {
+        // New outputs (Read all, the refreshes on a new block, a call from its button) are taken
+        // here rather than copied every frame, so the table is only decoded again when they
+        // change. The raw calldata result is picked up by its own section.
+        if let Some(outputs) = STATE
+            .temp_fn_output
+            .write()
+            .unwrap()
+            .get_mut(&format!("{:#x}", self.address_h160))
+        {
+            let selectors: Vec<String> = outputs
+                .keys()
+                .filter(|key| *key != RAW_CALLDATA_KEY)
+                .cloned()
                 .collect();
+            for selector in selectors {
+                if let Some(ret) = outputs.remove(&selector) {
+                    self.func_output.insert(selector, ret);
+                    self.state_rows = None;
+                }
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("Read all").clicked() {
+                self.read_all();
+                self.show_state_table = true;
+            }
+            if self.show_state_table && ui.button("Hide").clicked() {
+                self.show_state_table = false;
+            }
+        });
+        if !self.show_state_table {
+            return;
+        }
+
+        let rows = self.state_rows.get_or_insert_with(|| {
+            parameterless_views(&self.abi)
+                .into_iter()
+                .map(|abi_item| {
+                    let selector = abi::function_selector(abi_item);
+                    let value = match self.func_output.get(&selector) {
+                        Some(ReturnAndReceipt {
+                            revert_reason: Some(revert_reason),
+                            ..
+                        }) => format!("reverted: {}", revert_reason),
+                        Some(result) => match abi::decode_return_values(
+                            selector.clone(),
+                            &self.abi,
+                            &result.return_output,
+                        ) {
+                            Ok(tokens) => tokens
+                                .iter()
+                                .map(abi::format_token)
+                                .collect::<Vec<String>>()
+                                .join(", "),
+                            Err(e) => format!("decode error: {}", e),
+                        },
+                        None => "-".to_string(),
+                    };
+                    (
+                        abi_item["name"].as_str().unwrap_or_default().to_string(),
+                        value,
+                    )
+                })
+                .collect()
+        });
+        egui::Grid::new(("state_table", self.address_h160))
+            .striped(true)
+            .show(ui, |ui| {
+                for (name, value) in rows.iter() {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+    }
+
+    // One eth_call via Multicall3 for all the parameterless views. These don't go in the tx
+    // history, same as the refreshes on a new block.
+    pub fn read_all(&self) {
+        let selectors: Vec<String> = parameterless_views(&self.abi)
+            .into_iter()
+            .map(abi::function_selector)
+            .collect();
+        if selectors.is_empty() {
+            backend::send_error_popup("No parameterless view functions".to_string());
+            return;
+        }
+
+        let address_h160 = self.address_h160;
+        let abi = self.abi.clone();
+        let tx_configs = shared_state::read_tx_configs();
+        wasm_bindgen_futures::spawn_local(async move {
+            let res = async {
+                let calls = selectors
+                    .iter()
+                    .map(|selector| {
+                        Ok((
+                            address_h160,
+                            Bytes::from(abi::selector_to_bytes(selector)?.to_vec()),
+                        ))
+                    })
+                    .collect::<Result<Vec<(H160, Bytes)>>>()?;
+                backend::aggregate3(&calls, &tx_configs).await
+            }
+            .await;
 
-            // Sort functions based on state mutability
-            // Function to determine the priority of state mutability
-            let get_priority = |m: &str| -> i32 {
-                match m {
-                    "pure" => 1,
-                    "view" => 2,
-                    _ => 3,
+            let results = match res {
+                Ok(results) => results,
+                Err(e) => {
+                    backend::send_error_popup(format!("ERROR: {}", e));
+                    return;
                 }
             };
 
-            // Sort functions based on state mutability and then by name
-            functions.sort_by_key(|a| {
-                let mutability = a["stateMutability"].as_str().unwrap_or("");
-                let name = a["name"].as_str().unwrap_or("");
-                (get_priority(mutability), name)
-            });
+            let abis = abis_for_decoding(&abi);
+            let mut temp_fn_output = STATE.temp_fn_output.write().unwrap();
+            let outputs = temp_fn_output
+                .entry(format!("{:#x}", address_h160))
+                .or_insert_with(HashMap::new);
+            for (selector, (success, return_data)) in selectors.into_iter().zip(results) {
+                let ret = match success {
+                    true => ReturnAndReceipt {
+                        return_output: return_data,
+                        ..Default::default()
+                    },
+                    false => ReturnAndReceipt {
+                        revert_reason: Some(abi::decode_revert_data(&return_data, &abis)),
+                        ..Default::default()
+                    },
+                };
+                outputs.insert(selector, ret);
+            }
+        });
+    }
 
-            // Iterate through the sorted functions to display the buttons
-            for abi_item in functions.iter() {
+    // TODO: clean this way tf up
+    fn show_functions_and_inputs(&mut self, ui: &mut Ui) {
+        // Functions are picked out by index so show_function can borrow self mutably
+        let Some(abi_array) = self.abi.as_array() else {
+            return;
+        };
+        let mut functions: Vec<(usize, &Value)> = abi_array
+            .iter()
+            .enumerate()
+            .filter(|(_, abi_item)| {
                 if let Some(abi_type) = abi_item.get("type") {
-                    if abi_type == "function" {
-                        ui.separator();
-                        let func_name = abi_item["name"].as_str().unwrap_or("Unnamed function");
-
-                        // cool
-                        let is_static = matches!(
-                            abi_item["stateMutability"].as_str(),
-                            Some("view") | Some("pure")
-                        );
+                    abi_type == "function"
+                } else {
+                    false
+                }
+            })
+            .collect();
+
+        // Sort functions based on state mutability
+        // Function to determine the priority of state mutability
+        let get_priority = |m: &str| -> i32 {
+            match m {
+                "pure" => 1,
+                "view" => 2,
+                _ => 3,
+            }
+        };
 
-                        ui.horizontal(|ui| {
-                            // button color based on state mutability
-
-                            ui.with_layout(
-                                egui::Layout::left_to_right(egui::Align::Min)
-                                    .with_cross_align(egui::Align::Min),
-                                |ui| {
-                                    ui.style_mut().visuals.override_text_color =
-                                        Some(egui::Color32::BLACK);
-                                    match is_static {
-                                        true => {
-                                            ui.style_mut().visuals.widgets.inactive.weak_bg_fill =
-                                                egui::Color32::GRAY;
-                                        }
-                                        _ => {
-                                            ui.style_mut().visuals.widgets.inactive.weak_bg_fill =
-                                                egui::Color32::LIGHT_GRAY;
-                                        }
+        // Sort functions based on state mutability and then by name
+        functions.sort_by_key(|(_, a)| {
+            let mutability = a["stateMutability"].as_str().unwrap_or("");
+            let name = a["name"].as_str().unwrap_or("");
+            (get_priority(mutability), name)
+        });
+
+        // Overloaded functions get their full signature as the button label
+        let mut name_counts: HashMap<String, usize> = HashMap::new();
+        for (_, abi_item) in functions.iter() {
+            *name_counts
+                .entry(abi_item["name"].as_str().unwrap_or("").to_string())
+                .or_default() += 1;
+        }
+
+        // With an implementation's ABI attached, the proxy's own functions get their own section
+        let admin_selectors: Vec<String> = self
+            .proxy_abi
+            .as_ref()
+            .and_then(|proxy_abi| proxy_abi.as_array())
+            .map(|abi_items| abi_items.iter().map(abi::function_selector).collect())
+            .unwrap_or_default();
+        let (admin_functions, functions): (Vec<usize>, Vec<usize>) = functions
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .partition(|idx| admin_selectors.contains(&abi::function_selector(&abi_array[*idx])));
+
+        // Iterate through the sorted functions to display the buttons
+        for idx in functions {
+            self.show_function(ui, idx, &name_counts);
+        }
+        if !admin_functions.is_empty() {
+            ui.separator();
+            ui.collapsing("Admin (proxy)", |ui| {
+                for idx in admin_functions {
+                    self.show_function(ui, idx, &name_counts);
+                }
+            });
+        }
+    }
+
+    // `idx` is the function's position in the ABI
+    fn show_function(&mut self, ui: &mut Ui, idx: usize, name_counts: &HashMap<String, usize>) {
+        let abi_item = &self.abi[idx];
+        if let Some(abi_type) = abi_item.get("type") {
+            if abi_type == "function" {
+                ui.separator();
+                let func_name = abi_item["name"].as_str().unwrap_or("Unnamed function");
+                let selector = abi::function_selector(abi_item);
+                let button_text = match name_counts.get(func_name) {
+                    Some(count) if *count > 1 => abi::function_signature(abi_item),
+                    _ => format!("{}()", func_name),
+                };
+
+                // cool
+                let is_static = matches!(
+                    abi_item["stateMutability"].as_str(),
+                    Some("view") | Some("pure")
+                );
+
+                ui.horizontal(|ui| {
+                    // button color based on state mutability
+
+                    ui.with_layout(
+                        egui::Layout::left_to_right(egui::Align::Min)
+                            .with_cross_align(egui::Align::Min),
+                        |ui| {
+                            ui.style_mut().visuals.override_text_color = Some(egui::Color32::BLACK);
+                            match is_static {
+                                true => {
+                                    ui.style_mut().visuals.widgets.inactive.weak_bg_fill =
+                                        egui::Color32::GRAY;
+                                }
+                                _ => {
+                                    ui.style_mut().visuals.widgets.inactive.weak_bg_fill =
+                                        egui::Color32::LIGHT_GRAY;
+                                }
+                            }
+                            if ui.button(&button_text).clicked() {
+                                // Clone only the parts of `self` that you need.
+                                match is_static {
+                                    true => {
+                                        self.cursed_staticcall_wrapper(selector.clone());
                                     }
-                                    if ui.button(format!("{}()", func_name)).clicked() {
-                                        // Clone only the parts of `self` that you need.
-                                        match is_static {
-                                            true => {
-                                                self.cursed_staticcall_wrapper(
-                                                    func_name.to_string(),
-                                                );
-                                            }
-                                            _ => {
-                                                self.cursed_send_wrapper(func_name.to_string());
-                                            }
-                                        }
+                                    _ => {
+                                        self.cursed_send_wrapper(selector.clone());
                                     }
-                                },
-                            );
-                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
-                                UtilityMenu::show_for_function(
-                                    ui,
-                                    self,
-                                    self.abi.clone(),
-                                    abi_item,
-                                    func_name.to_string(),
-                                );
-                            });
-                        });
-
-                        // Create input fields for each parameter
-                        if let Some(inputs) = abi_item.get("inputs") {
-                            if let Some(input_array) = inputs.as_array() {
-                                for param in input_array.iter() {
-                                    let param_name =
-                                        param["name"].as_str().unwrap_or("Unnamed parameter");
-                                    let param_type =
-                                        param["type"].as_str().unwrap_or("Unknown type");
-
-                                    // Create an input field for each parameter
-                                    let placeholder: String =
-                                        format!("{}: {}", param_name, param_type);
-                                    let func_name = func_name.to_string();
-                                    let param_name = param_name.to_string();
-                                    let text: &mut String = self
-                                        .func_param_input
-                                        .entry(func_name.clone())
-                                        .or_insert_with(HashMap::new)
-                                        .entry(param_name.clone())
-                                        .or_default();
-                                    // let id = egui::Id::new(param_name.clone());
-
-                                    ui.set_max_width(
-                                        ui.available_width() + ui.spacing().item_spacing.x,
-                                    );
-                                    let text_edit = egui::TextEdit::singleline(text)
-                                        .hint_text(&placeholder)
-                                        .desired_width(f32::INFINITY)
-                                        .show(ui);
-                                    ui.set_max_width(
-                                        ui.available_width() - ui.spacing().item_spacing.x,
-                                    );
                                 }
                             }
+                        },
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                        UtilityMenu::show_for_function(
+                            ui,
+                            self,
+                            &self.abi,
+                            &abi_item,
+                            selector.clone(),
+                        );
+                    });
+                });
+
+                // Create input fields for each parameter
+                if let Some(inputs) = abi_item.get("inputs") {
+                    if let Some(input_array) = inputs.as_array() {
+                        for param in input_array.iter() {
+                            let param_name = param["name"].as_str().unwrap_or("Unnamed parameter");
+                            let param_type = abi::param_type_string(param);
+
+                            // Create an input field for each parameter
+                            let placeholder: String = format!("{}: {}", param_name, param_type);
+                            let param_name = param_name.to_string();
+                            let text: &mut String = self
+                                .func_param_input
+                                .entry(selector.clone())
+                                .or_insert_with(HashMap::new)
+                                .entry(param_name.clone())
+                                .or_default();
+                            // let id = egui::Id::new(param_name.clone());
+
+                            ui.set_max_width(ui.available_width() + ui.spacing().item_spacing.x);
+                            let text_edit = egui::TextEdit::singleline(text)
+                                .hint_text(&placeholder)
+                                .desired_width(f32::INFINITY)
+                                .show(ui);
+                            ui.set_max_width(ui.available_width() - ui.spacing().item_spacing.x);
                         }
+                    }
+                }
 
-                        // Show return data
-                        // Shared state wrangling
-                        let temp_fn_output_lock = STATE.temp_fn_output.read().unwrap();
-                        // Attempt to get the inner HashMap using self.address_h160 as the key
-                        if let Some(inner_map) =
-                            temp_fn_output_lock.get(&format!("{:#x}", self.address_h160))
-                        {
-                            // Attempt to get the specific output value using func_name as the key
-                            if let Some(ret) = inner_map.get(func_name) {
-                                // Insert the retrieved output_value into self.func_output
-                                self.func_output.insert(func_name.to_string(), ret.clone());
-                            }
-                        }
+                // Show return data
+                // Shared state wrangling
+                let temp_fn_output_lock = STATE.temp_fn_output.read().unwrap();
+                // Attempt to get the inner HashMap using self.address_h160 as the key
+                if let Some(inner_map) =
+                    temp_fn_output_lock.get(&format!("{:#x}", self.address_h160))
+                {
+                    // Attempt to get the specific output value using the selector as the key
+                    if let Some(ret) = inner_map.get(&selector) {
+                        // Insert the retrieved output_value into self.func_output
+                        self.func_output.insert(selector.clone(), ret.clone());
+                    }
+                }
 
-                        // Draw the return data if available
-                        if let Some(result) = self.func_output.get(func_name) {
-                            ui.horizontal_wrapped(|ui| {
-                                ui.label("return:");
-                                // CopyButton::new(
-                                //     "Copy Raw".to_string(),
-                                //     format!("{}", result.return_output),
-                                // )
-                                // .show(ui);
-                            });
-
-                            // Get the return type from the ABI
-                            if let Some(outputs) = abi_item.get("outputs") {
-                                if let Some(output_array) = outputs.as_array() {
-                                    for (index, output) in output_array.iter().enumerate() {
-                                        let index_string = index.to_string();
-
-                                        let output_type =
-                                            output["type"].as_str().unwrap_or("Unknown type");
-                                        let output_name = match output["name"].as_str() {
-                                            Some(name) if !name.is_empty() => format!("{} ", name),
-                                            _ => format!("val_{}", index_string.clone()),
-                                        };
-
-                                        let decode_result = abi::decode_return_values(
-                                            func_name.to_string(),
-                                            &self.abi,
-                                            &result.return_output,
-                                        );
-                                        let formatted_value = match decode_result {
-                                            Ok(decoded_return) => {
-                                                let return_value = &decoded_return[index];
-
-                                                let zeroes_to_prepend =
-                                                    if format!("{}", return_value).len() % 2 == 1 {
-                                                        "0"
-                                                    } else {
-                                                        ""
-                                                    };
-
-                                                match output_type {
-                                                    t if t.starts_with("uint")
-                                                        || t.starts_with("int") =>
-                                                    {
-                                                        if let Token::Uint(value)
-                                                        | Token::Int(value) = return_value
-                                                        {
-                                                            format!(
-                                                                "0x{}{:x}",
-                                                                zeroes_to_prepend, value
-                                                            )
-                                                        } else {
-                                                            format!("{}", return_value)
-                                                        }
-                                                    }
-                                                    t if t.starts_with("bytes")
-                                                        || t == "address" =>
-                                                    {
-                                                        format!(
-                                                            "0x{}{}",
-                                                            zeroes_to_prepend, return_value
-                                                        )
-                                                    }
-                                                    _ => format!("{}", return_value),
+                // Draw the revert reason in place of the return data if the call reverted
+                let revert_reason = self
+                    .func_output
+                    .get(&selector)
+                    .and_then(|result| result.revert_reason.clone());
+
+                if let Some(revert_reason) = revert_reason {
+                    ui.add(
+                        egui::TextEdit::multiline(
+                            &mut format!("reverted: {}", revert_reason).as_str(),
+                        )
+                        .desired_rows(1)
+                        .desired_width(f32::INFINITY),
+                    );
+                // Draw the return data if available
+                } else if let Some(result) = self.func_output.get(&selector) {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label("return:");
+                        // CopyButton::new(
+                        //     "Copy Raw".to_string(),
+                        //     format!("{}", result.return_output),
+                        // )
+                        // .show(ui);
+                    });
+
+                    // Get the return type from the ABI
+                    if let Some(outputs) = abi_item.get("outputs") {
+                        if let Some(output_array) = outputs.as_array() {
+                            for (index, output) in output_array.iter().enumerate() {
+                                let index_string = index.to_string();
+
+                                let output_type = output["type"].as_str().unwrap_or("Unknown type");
+                                let output_name = match output["name"].as_str() {
+                                    Some(name) if !name.is_empty() => format!("{} ", name),
+                                    _ => format!("val_{}", index_string.clone()),
+                                };
+
+                                let decode_result = abi::decode_return_values(
+                                    selector.clone(),
+                                    &self.abi,
+                                    &result.return_output,
+                                );
+                                let formatted_value = match decode_result {
+                                    Ok(decoded_return) => {
+                                        let return_value = &decoded_return[index];
+
+                                        let zeroes_to_prepend =
+                                            if format!("{}", return_value).len() % 2 == 1 {
+                                                "0"
+                                            } else {
+                                                ""
+                                            };
+
+                                        match output_type {
+                                            t if t.starts_with("uint") || t.starts_with("int") => {
+                                                if let Token::Uint(value) = return_value {
+                                                    format!("0x{}{:x}", zeroes_to_prepend, value)
+                                                } else {
+                                                    // signed ints render as decimals
+                                                    abi::format_token(return_value)
                                                 }
                                             }
-                                            Err(e) => "0x".to_string(), // error decoding output, just print 0x
-                                        };
-
-                                        ui.add(
-                                            egui::TextEdit::multiline(
-                                                &mut format!(
-                                                    "{} ({}): {}  \n",
-                                                    output_name, output_type, formatted_value
-                                                )
-                                                .trim(),
-                                            )
-                                            .desired_rows(1)
-                                            .desired_width(f32::INFINITY),
-                                        );
+                                            t if t.starts_with("bytes") || t == "address" => {
+                                                format!("0x{}{}", zeroes_to_prepend, return_value)
+                                            }
+                                            _ => abi::format_token(return_value),
+                                        }
                                     }
-                                }
+                                    Err(e) => "0x".to_string(), // error decoding output, just print 0x
+                                };
+
+                                ui.add(
+                                    egui::TextEdit::multiline(
+                                        &mut format!(
+                                            "{} ({}): {}  \n",
+                                            output_name, output_type, formatted_value
+                                        )
+                                        .trim(),
+                                    )
+                                    .desired_rows(1)
+                                    .desired_width(f32::INFINITY),
+                                );
                             }
                         }
                     }
@@ -342,113 +1012,455 @@ impl DeployedContract {
     fn show_calldata_area(&mut self, ui: &mut Ui) {
         ui.separator();
 
-        ui.label("Enter Raw Calldata:");
+        // Whatever the dispatcher checks for, so code loaded without an ABI is still callable.
+        // Without an ABI only the artifacts' ABIs can name them (and decode the calldata below),
+        // so load those and wait; otherwise that's only done when asked for.
+        let has_abi = self.abi.as_array().is_some_and(|abi| !abi.is_empty());
+        if !has_abi {
+            backend::query_for_artifact_codes();
+        }
+        let (codes_requested, codes_loaded) = {
+            let artifact_index = STATE.artifact_index.read().unwrap();
+            (artifact_index.codes_requested, artifact_index.codes_loaded)
+        };
+        if self.dispatcher_selectors.is_none() && (codes_loaded || !codes_requested) {
+            let selectors =
+                disassembler::dispatcher_selectors(&disassembler::disassemble(&self.bytecode_b));
+            self.dispatcher_selectors = Some(disassembler::resolve_selectors(
+                &selectors,
+                &abis_for_rendering(&self.abi),
+            ));
+        }
+        let presets = self.dispatcher_selectors.as_deref().unwrap_or_default();
+        let mut resolve_selectors = false;
+        ui.horizontal(|ui| {
+            ui.label("Enter Raw Calldata:");
+            if codes_requested && !codes_loaded {
+                ui.label("Loading artifact ABIs...");
+            } else if !codes_loaded
+                && ui
+                    .button("Resolve selectors")
+                    .on_hover_text(
+                        "Name the selectors (and decode calldata) with every artifact's ABI",
+                    )
+                    .clicked()
+            {
+                resolve_selectors = true;
+            }
+            if !presets.is_empty() {
+                egui::ComboBox::from_id_source(("calldata_presets", self.address_h160))
+                    .selected_text("Selectors")
+                    .show_ui(ui, |ui| {
+                        for preset in presets.iter() {
+                            let text = match &preset.signature {
+                                Some(signature) => format!("{} {}", preset.selector, signature),
+                                None => preset.selector.clone(),
+                            };
+                            if ui.selectable_label(false, text).clicked() {
+                                self.calldata_input = preset.selector.clone();
+                            }
+                        }
+                    });
+            }
+        });
+        // Named again once the artifacts' ABIs are in
+        if resolve_selectors {
+            backend::query_for_artifact_codes();
+            self.dispatcher_selectors = None;
+        }
         let calldata: &mut String = &mut self.calldata_input;
         egui::TextEdit::multiline(calldata)
             .desired_width(f32::INFINITY)
             .show(ui);
-        // Display buttons for raw verisons of "send" and "staticcall"
-        ui.horizontal(|ui| {
-            // optionally strip 0x prefix
-            let input = self
-                .calldata_input
-                .strip_prefix("0x")
-                .unwrap_or(&self.calldata_input)
-                .to_string();
 
-            if ui.button("Send").clicked() {
+        // optionally strip 0x prefix
+        let input = self
+            .calldata_input
+            .trim()
+            .strip_prefix("0x")
+            .unwrap_or(self.calldata_input.trim())
+            .to_string();
+        // What it'll call, before it's sent; only decoded again once the input or the known
+        // functions change
+        if !input.is_empty() {
+            let generation = STATE.selector_registry.read().unwrap().generation;
+            let stale = self.decoded_calldata.as_ref().map_or(
+                true,
+                |(decoded_input, decoded_generation, _)| {
+                    *decoded_input != input || *decoded_generation != generation
+                },
+            );
+            if stale {
+                let decoded = match decode_raw_calldata(&self.name, &self.abi, &input) {
+                    Ok(decoded) => decoded.to_string(),
+                    Err(e) => e.to_string(),
+                };
+                self.decoded_calldata = Some((input.clone(), generation, decoded));
+            }
+            if let Some((_, _, decoded)) = &self.decoded_calldata {
+                ui.label(decoded.as_str());
+            }
+        }
+
+        // Display buttons for raw verisons of "send" and "staticcall", only once it's hex
+        let valid_hex = input.len() % 2 == 0 && input.bytes().all(|b| b.is_ascii_hexdigit());
+        ui.horizontal(|ui| {
+            let send = ui
+                .add_enabled(valid_hex, egui::Button::new("Send"))
+                .on_disabled_hover_text("Calldata isn't valid hex");
+            let staticcall = ui
+                .add_enabled(valid_hex, egui::Button::new("Staticcall"))
+                .on_disabled_hover_text("Calldata isn't valid hex");
+            if send.clicked() {
                 self.cursed_send_raw_wrapper(input);
-            } else if ui.button("Staticcall").clicked() {
+            } else if staticcall.clicked() {
                 self.cursed_staticcall_raw_wrapper(input);
             }
         });
 
-        // YUCK!
-        let temp_fn_output_lock = STATE.temp_fn_output.read().unwrap();
-        // Attempt to get the inner HashMap using self.address_h160 as the key
-        if let Some(inner_map) = temp_fn_output_lock.get(&format!("{:#x}", self.address_h160)) {
-            // Attempt to get the specific output value using func_name as the key
-            if let Some(ret) = inner_map.get("hope nobody else uses this name for a function") {
-                // Insert the retrieved output_value into self.func_output
-                self.func_output.insert(
-                    "hope nobody else uses this name for a function".to_string(),
-                    ret.clone(),
-                );
-            }
+        // Taken rather than copied every frame, so a new result is decoded once
+        if let Some(ret) = STATE
+            .temp_fn_output
+            .write()
+            .unwrap()
+            .get_mut(&format!("{:#x}", self.address_h160))
+            .and_then(|outputs| outputs.remove(RAW_CALLDATA_KEY))
+        {
+            self.func_output.insert(RAW_CALLDATA_KEY.to_string(), ret);
+            self.raw_output_text = None;
+        }
+        if let Some(result) = self.func_output.get(RAW_CALLDATA_KEY) {
+            let output = self.raw_output_text.get_or_insert_with(|| {
+                match &result.revert_reason {
+                    Some(revert_reason) => format!("Reverted: \n{}", revert_reason),
+                    // decoded with the outputs of whatever the calldata it was sent with matched
+                    None => match result
+                        .inputs
+                        .get(RAW_CALLDATA_INPUT)
+                        .ok_or(eyre!("No calldata"))
+                        .and_then(|calldata| decode_raw_calldata(&self.name, &self.abi, calldata))
+                        .and_then(|call| {
+                            selectors::decode_output(&call.function.abi_item, &result.return_output)
+                        }) {
+                        Ok(decoded) if !decoded.is_empty() => {
+                            format!("Return: \n{}\nDecoded: {}", result.return_output, decoded)
+                        }
+                        _ => format!("Return: \n{}", result.return_output),
+                    },
+                }
+            });
+            ui.add(egui::TextEdit::multiline(&mut output.trim()).desired_rows(1));
+        }
+    }
+
+    fn show_disassembly(&mut self, ui: &mut Ui) {
+        ui.collapsing("Disassembly", |ui| {
+            let bytecode_b = &self.bytecode_b;
+            let abi = &self.abi;
+            self.disassembly
+                .get_or_insert_with(|| {
+                    DisassemblyView::new(&[("Runtime", bytecode_b)], &abis_for_rendering(abi))
+                })
+                .show(ui, self.address_h160);
+        });
+    }
+
+    fn show_events(&mut self, ui: &mut Ui) {
+        // Pick up any logs the backend has posted for this address
+        let address_key = format!("{:#x}", self.address_h160);
+        if let Some(new_logs) = STATE
+            .temp_receipt_logs
+            .write()
+            .unwrap()
+            .remove(&address_key)
+        {
+            self.receipt_logs.extend(new_logs);
         }
-        if let Some(result) = self
-            .func_output
-            .get("hope nobody else uses this name for a function")
+        if let Some(mut page) = STATE
+            .temp_historical_logs
+            .write()
+            .unwrap()
+            .remove(&address_key)
         {
-            ui.add(
-                egui::TextEdit::multiline(
-                    &mut format!("Return: \n{}", result.return_output).trim(),
+            // older pages go in front of what's already been fetched
+            if page.older {
+                page.logs.append(&mut self.historical_logs);
+            }
+            self.historical_logs = page.logs;
+            self.history_from_block = Some(page.searched_from);
+        }
+        if let Some(live_logs) = STATE.temp_live_logs.write().unwrap().remove(&address_key) {
+            let event_topic = self
+                .event_filter
+                .as_ref()
+                .map(|signature| H256::from(ethers::utils::keccak256(signature)));
+            self.historical_logs
+                .extend(live_logs.into_iter().filter(|decoded| {
+                    event_topic.map_or(true, |topic| decoded.log.topics.first() == Some(&topic))
+                }));
+        }
+
+        ui.separator();
+        ui.collapsing("Events", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("From receipts:");
+                if ui.button("Clear").clicked() {
+                    self.receipt_logs.clear();
+                }
+            });
+            for log in self.receipt_logs.iter() {
+                log.show(ui);
+            }
+
+            // Historical logs via eth_getLogs, optionally filtered down to a single event
+            let event_signatures: Vec<String> = self
+                .abi
+                .as_array()
+                .map(|abi_items| {
+                    abi_items
+                        .iter()
+                        .filter(|abi_item| abi_item["type"] == "event")
+                        .map(abi::function_signature)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let previous_filter = self.event_filter.clone();
+            ui.horizontal(|ui| {
+                ui.label("History:");
+                egui::ComboBox::from_id_source(format!("event_filter_{}", address_key))
+                    .selected_text(
+                        self.event_filter
+                            .clone()
+                            .unwrap_or_else(|| "All events".to_string()),
+                    )
+                    .width(ui.available_width() * 0.6)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.event_filter, None, "All events");
+                        for signature in event_signatures.iter() {
+                            ui.selectable_value(
+                                &mut self.event_filter,
+                                Some(signature.clone()),
+                                signature,
+                            );
+                        }
+                    });
+
+                if ui.button("Fetch").clicked() {
+                    let event_topic = self
+                        .event_filter
+                        .as_ref()
+                        .map(|signature| H256::from(ethers::utils::keccak256(signature)));
+                    backend::get_logs_wrapper(
+                        self.address_h160,
+                        self.abi.clone(),
+                        self.deployed_block.number.unwrap_or_default(),
+                        None,
+                        event_topic,
+                    );
+                }
+            });
+            // Paging back is only for the filter the history was fetched with
+            if self.event_filter != previous_filter {
+                self.history_from_block = None;
+            }
+
+            // A single fetch only reaches MAX_LOG_BLOCKS back, so say where it stopped
+            let deployed_at = self.deployed_block.number.unwrap_or_default();
+            if let Some(from_block) = self
+                .history_from_block
+                .filter(|from_block| *from_block > deployed_at.as_u64())
+            {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Searched from block {} ({} blocks per fetch), older blocks weren't",
+                        from_block,
+                        backend::MAX_LOG_BLOCKS
+                    ));
+                    if ui.button("Fetch older").clicked() {
+                        let event_topic = self
+                            .event_filter
+                            .as_ref()
+                            .map(|signature| H256::from(ethers::utils::keccak256(signature)));
+                        backend::get_logs_wrapper(
+                            self.address_h160,
+                            self.abi.clone(),
+                            deployed_at,
+                            Some(U64::from(from_block - 1)),
+                            event_topic,
+                        );
+                    }
+                });
+            }
+            for log in self.historical_logs.iter() {
+                log.show(ui);
+            }
+        });
+    }
+
+    pub fn cursed_send_wrapper(&self, selector: String) {
+        self.function_call_wrapper(TxKind::Send, selector);
+    }
+
+    pub fn cursed_staticcall_wrapper(&self, selector: String) {
+        self.function_call_wrapper(TxKind::Staticcall, selector);
+    }
+
+    fn function_call_wrapper(&self, kind: TxKind, selector: String) {
+        DeployedContract::spawn_function_call(
+            kind,
+            self.address_h160,
+            self.name.clone(),
+            self.abi.clone(),
+            selector,
+            self.func_param_input.clone(),
+            shared_state::read_tx_configs(),
+        );
+    }
+
+    // Re-runs the view calls that already have an output showing, with the inputs that output
+    // came from rather than whatever's typed now, e.g. when a new block lands. These don't go
+    // in the tx history.
+    pub fn refresh_view_outputs(&self) {
+        let tx_configs = shared_state::read_tx_configs();
+        for (selector, output) in self.func_output.iter() {
+            if output.tx_receipt.is_some() {
+                continue;
+            }
+            let is_view =
+                abi::function_item_by_selector(&self.abi, selector).is_some_and(|abi_item| {
+                    matches!(abi_item["stateMutability"].as_str(), Some("view" | "pure"))
+                });
+            if !is_view {
+                continue;
+            }
+
+            let address_h160 = self.address_h160;
+            let abi = self.abi.clone();
+            let selector = selector.clone();
+            let inputs = output.inputs.clone();
+            let mut func_param_input = HashMap::new();
+            func_param_input.insert(selector.clone(), inputs.clone());
+            let tx_configs = tx_configs.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut ret = match DeployedContract::staticcall(
+                    address_h160,
+                    abi.clone(),
+                    selector.clone(),
+                    func_param_input,
+                    tx_configs,
                 )
-                .desired_rows(1),
-            );
+                .await
+                {
+                    Ok(ret) => ret,
+                    // Keep showing the last output if the call fails outright
+                    Err(e) => match abi::revert_data_from_error(&e) {
+                        Some(revert_data) => ReturnAndReceipt {
+                            revert_reason: Some(abi::decode_revert_data(
+                                &revert_data,
+                                &abis_for_decoding(&abi),
+                            )),
+                            ..Default::default()
+                        },
+                        None => return,
+                    },
+                };
+                ret.inputs = inputs;
+
+                STATE
+                    .temp_fn_output
+                    .write()
+                    .unwrap()
+                    .entry(format!("{:#x}", address_h160))
+                    .or_insert_with(HashMap::new)
+                    .insert(selector, ret);
+            });
         }
     }
 
-    pub fn cursed_send_wrapper(&self, func_name: String) {
-        // cloning makes
-        let address_h160 = self.address_h160;
-        let abi = self.abi.clone();
-        let func_param_input = self.func_param_input.clone();
+    // Associated rather than a method so history entries can be rerun after the contract
+    // has been removed from the deployed list
+    pub fn spawn_function_call(
+        kind: TxKind,
+        address_h160: H160,
+        name: String,
+        abi: Value,
+        selector: String,
+        func_param_input: HashMap<String, HashMap<String, String>>,
+        tx_configs: TxConfigs,
+    ) {
+        // Only keep the called function + its inputs in the history entry
+        let abi_item = abi::function_item_by_selector(&abi, &selector)
+            .cloned()
+            .unwrap_or_default();
+        let inputs = func_param_input.get(&selector).cloned().unwrap_or_default();
+        let call = abi::describe_fn_call(&abi_item, &inputs);
+        let mut replay_input = HashMap::new();
+        replay_input.insert(selector.clone(), inputs.clone());
+
+        let record = TxRecord::new(
+            kind,
+            address_h160,
+            name,
+            call,
+            tx_configs.clone(),
+            TxReplay::Function {
+                abi_item,
+                selector: selector.clone(),
+                func_param_input: replay_input,
+            },
+        );
 
         // Spawn the future
         wasm_bindgen_futures::spawn_local(async move {
-            // have to qualify here bc middleware also has a send_transaction?
-            let res = DeployedContract::send_transaction(
-                address_h160,
-                abi,
-                func_name.to_string(),
-                func_param_input,
-            )
-            .await;
-
-            let mut temp_fn_output_write_lock = STATE.temp_fn_output.write().unwrap();
-            let inner_map = temp_fn_output_write_lock
-                .entry(format!("{:#x}", address_h160))
-                .or_insert_with(HashMap::new);
-
-            match res {
-                Ok(ret) => {
-                    inner_map.insert(func_name.to_string(), ret);
+            let res = match kind {
+                TxKind::Staticcall => {
+                    DeployedContract::staticcall(
+                        address_h160,
+                        abi.clone(),
+                        selector.clone(),
+                        func_param_input,
+                        tx_configs,
+                    )
+                    .await
                 }
-                Err(e) => {
-                    backend::send_error_popup(format!("ERROR: {}", e));
+                // have to qualify here bc middleware also has a send_transaction?
+                _ => {
+                    DeployedContract::send_transaction(
+                        address_h160,
+                        abi.clone(),
+                        selector.clone(),
+                        func_param_input,
+                        tx_configs,
+                    )
+                    .await
                 }
-            }
+            };
+
+            DeployedContract::store_call_result(address_h160, selector, &abi, res, inputs, record);
         });
     }
 
     async fn send_transaction(
         address: H160,
         json_abi: Value,
-        func_name: String,
+        selector: String,
         func_param_input: HashMap<String, HashMap<String, String>>,
+        tx_configs: TxConfigs,
     ) -> Result<ReturnAndReceipt> {
         let client_wrapper = shared_state::read_shared_client()?;
-        let client = client_wrapper.client;
-
-        let tx_configs = shared_state::read_tx_configs();
+        let client = client_wrapper.client_for(tx_configs.from_address);
 
         let ethers_abi: ethers::abi::Abi = serde_json::from_value(json_abi.clone()).unwrap();
         let ethers_contract =
             ethers::contract::Contract::new(address, ethers_abi, Arc::new(client.clone()));
 
-        let tokens =
-            abi::encode_fn_call_to_tokens(func_name.to_string(), json_abi, func_param_input)?;
+        let tokens = abi::encode_fn_call_to_tokens(selector.clone(), json_abi, func_param_input)?;
         // log!("{:?}", tokens);
 
-        let mut fn_call = ethers_contract.method::<_, Vec<Token>>(&func_name, &tokens[..])?;
-        fn_call.tx.set_from(tx_configs.from_address);
-
-        fn_call
-            .tx
-            .set_value(utils::eth_str_to_u256_wei(&tx_configs.value)?);
-
-        fn_call.tx.set_gas_price(tx_configs.gas_price);
+        let mut fn_call = ethers_contract
+            .method_hash::<_, Vec<Token>>(abi::selector_to_bytes(&selector)?, &tokens[..])?;
+        utils::apply_tx_configs(&mut fn_call.tx, &tx_configs)?;
 
         log!("{:?}", fn_call.tx);
         let static_return = client.provider().call_raw(&fn_call.tx).await?;
@@ -461,49 +1473,21 @@ impl DeployedContract {
         Ok(ReturnAndReceipt {
             tx_receipt: Some(tx_receipt),
             return_output: static_return,
+            revert_reason: None,
+            ..Default::default()
         })
     }
 
-    pub fn cursed_staticcall_wrapper(&self, func_name: String) {
-        let to_address = self.address_h160;
-        let abi = self.abi.clone();
-        let func_param_input = self.func_param_input.clone();
-
-        // Spawn the future
-        wasm_bindgen_futures::spawn_local(async move {
-            let res = DeployedContract::staticcall(
-                to_address,
-                abi,
-                func_name.to_string(),
-                func_param_input,
-            )
-            .await;
-
-            let mut temp_fn_output_write_lock = STATE.temp_fn_output.write().unwrap();
-            let inner_map = temp_fn_output_write_lock
-                .entry(format!("{:#x}", to_address))
-                .or_insert_with(HashMap::new);
-
-            match res {
-                Ok(ret) => {
-                    inner_map.insert(func_name.to_string(), ret);
-                }
-                Err(e) => backend::send_error_popup(format!("ERROR: {}", e)),
-            }
-        });
-    }
-
-    async fn staticcall(
+    pub async fn staticcall(
         address_h160: H160,
         json_abi: Value,
-        func_name: String,
+        selector: String,
         func_param_input: HashMap<String, HashMap<String, String>>,
+        tx_configs: TxConfigs,
     ) -> Result<ReturnAndReceipt> {
         let client_wrapper = shared_state::read_shared_client()?;
         let client = client_wrapper.client;
 
-        let tx_configs = shared_state::read_tx_configs();
-
         let abi: ethers::abi::Abi = serde_json::from_value(json_abi.clone())?;
 
         let ethers_contract =
@@ -511,23 +1495,14 @@ impl DeployedContract {
 
         // let mut tokens: Vec<Token> = Vec::new();
         // let tokens = deployed_contract.abi_encode_to_tokens(func_name.to_string())?;
-        let tokens = abi::encode_fn_call_to_tokens(
-            func_name.to_string(),
-            json_abi.clone(),
-            func_param_input,
-        )?;
+        let tokens =
+            abi::encode_fn_call_to_tokens(selector.clone(), json_abi.clone(), func_param_input)?;
 
         // create the function call using the typed/encoded tokens
         let mut call = ethers_contract
-            .method::<_, Vec<Token>>(&func_name, &tokens[..])
-            .expect("Failed to generate call");
+            .method_hash::<_, Vec<Token>>(abi::selector_to_bytes(&selector)?, &tokens[..])?;
 
-        call.tx.set_from(tx_configs.from_address);
-
-        // annoying, want to abstract this to another fn
-
-        call.tx
-            .set_value(utils::eth_str_to_u256_wei(&tx_configs.value)?);
+        utils::apply_tx_configs(&mut call.tx, &tx_configs)?;
 
         let res = client
             .provider()
@@ -535,69 +1510,115 @@ impl DeployedContract {
             .await
             .map_err(|error| {
                 log::error!("ProviderError encountered: {:?}", error);
-                Box::new(error)
+                error
             })?;
         Ok(ReturnAndReceipt {
             return_output: res,
             tx_receipt: None,
+            revert_reason: None,
+            ..Default::default()
         })
     }
 
     pub fn cursed_send_raw_wrapper(&self, raw_calldata: String) {
-        let address_h160 = self.address_h160;
+        self.raw_call_wrapper(TxKind::Send, raw_calldata);
+    }
 
-        // Spawn the future
-        wasm_bindgen_futures::spawn_local(async move {
-            let res = DeployedContract::send_transaction_raw(address_h160, raw_calldata).await;
+    pub fn cursed_staticcall_raw_wrapper(&self, raw_calldata: String) {
+        self.raw_call_wrapper(TxKind::Staticcall, raw_calldata);
+    }
 
-            log!("{:?}", res);
-            let mut temp_fn_output_write_lock = STATE.temp_fn_output.write().unwrap();
-            let inner_map = temp_fn_output_write_lock
-                .entry(format!("{:#x}", address_h160))
-                .or_insert_with(HashMap::new);
+    fn raw_call_wrapper(&self, kind: TxKind, raw_calldata: String) {
+        DeployedContract::spawn_raw_call(
+            kind,
+            self.address_h160,
+            self.name.clone(),
+            self.abi.clone(),
+            raw_calldata,
+            shared_state::read_tx_configs(),
+        );
+    }
 
-            match res {
-                Ok(ret) => {
-                    inner_map.insert(
-                        "hope nobody else uses this name for a function".to_string(),
-                        ret,
-                    );
+    pub fn spawn_raw_call(
+        kind: TxKind,
+        address_h160: H160,
+        name: String,
+        abi: Value,
+        raw_calldata: String,
+        tx_configs: TxConfigs,
+    ) {
+        let call = match decode_raw_calldata(&name, &abi, &raw_calldata) {
+            Ok(decoded) => format!("raw {}", decoded),
+            Err(_) => format!("raw({})", raw_calldata),
+        };
+        let record = TxRecord::new(
+            kind,
+            address_h160,
+            name,
+            call,
+            tx_configs.clone(),
+            TxReplay::RawCalldata {
+                calldata: raw_calldata.clone(),
+            },
+        );
+
+        // The result is decoded with the calldata it was sent with, not whatever's in the input
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            RAW_CALLDATA_INPUT.to_string(),
+            raw_calldata
+                .strip_prefix("0x")
+                .unwrap_or(&raw_calldata)
+                .to_string(),
+        );
+
+        // Spawn the future
+        wasm_bindgen_futures::spawn_local(async move {
+            let res = match kind {
+                TxKind::Staticcall => {
+                    DeployedContract::staticcall_raw(address_h160, raw_calldata, tx_configs).await
                 }
-                Err(e) => {
-                    backend::send_error_popup(format!("ERROR: {}", e));
+                _ => {
+                    DeployedContract::send_transaction_raw(address_h160, raw_calldata, tx_configs)
+                        .await
                 }
-            }
+            };
+
+            log!("{:?}", res);
+            DeployedContract::store_call_result(
+                address_h160,
+                RAW_CALLDATA_KEY.to_string(),
+                &abi,
+                res,
+                inputs,
+                record,
+            );
         });
     }
 
     async fn send_transaction_raw(
         address_h160: H160,
         raw_calldata: String,
+        tx_configs: TxConfigs,
     ) -> Result<ReturnAndReceipt> {
         // Decode the provided calldata into ethers Bytes
-        let calldata_bytes =
-            Bytes::from(hex::decode(&raw_calldata).expect("Error decoding calldata"));
+        let calldata_bytes = Bytes::from(
+            hex::decode(raw_calldata.strip_prefix("0x").unwrap_or(&raw_calldata))
+                .map_err(|e| eyre!("Calldata isn't valid hex: {}", e))?,
+        );
 
         let client_wrapper = shared_state::read_shared_client()?;
-        let client = client_wrapper.client;
-
-        let tx_configs = shared_state::read_tx_configs();
+        let client = client_wrapper.client_for(tx_configs.from_address);
 
         let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::default());
-
-        // set the fron before we fill so we get the correct nonce
-        tx.set_from(tx_configs.from_address);
-        client.fill_transaction(&mut tx, None).await?;
-
-        // double the estimated gas limit
-        // hack until we implement gas field
-        tx.set_gas(tx.clone().gas().unwrap_or(&U256::zero()) * U256::from(2));
-        tx.set_value(utils::eth_str_to_u256_wei(&tx_configs.value)?);
-        tx.set_gas_price(tx_configs.gas_price);
-
         tx.set_to(address_h160);
         tx.set_data(calldata_bytes);
 
+        // set the from (and any overrides) before we fill so we get the correct nonce,
+        // and so the gas estimate covers the calldata
+        utils::apply_tx_configs(&mut tx, &tx_configs)?;
+        client.fill_transaction(&mut tx, None).await?;
+
         log!("{:?}", tx);
         let static_return = client.provider().call_raw(&tx).await?;
         log!("static return: {:?}", static_return);
@@ -609,61 +1630,100 @@ impl DeployedContract {
             Some(receipt) => Ok(ReturnAndReceipt {
                 tx_receipt: Some(receipt),
                 return_output: static_return,
+                revert_reason: None,
+                ..Default::default()
             }),
             None => Err(eyre!("Transaction receipt is None")),
         }
     }
 
-    pub fn cursed_staticcall_raw_wrapper(&self, raw_calldata: String) {
-        let address_h160 = self.address_h160;
-
-        // Spawn the future
-        wasm_bindgen_futures::spawn_local(async move {
-            let res = DeployedContract::staticcall_raw(address_h160, raw_calldata).await;
+    // Stores the result of a call in shared state for the frontend to pick up on the next
+    // frame. Reverts are decoded against this contract's ABI (then everything else we know
+    // about) and shown inline under the function; any other error gets a popup.
+    // Either way the outcome is added to the tx history.
+    fn store_call_result(
+        address_h160: H160,
+        key: String,
+        abi: &Value,
+        res: Result<ReturnAndReceipt>,
+        inputs: HashMap<String, String>,
+        mut record: TxRecord,
+    ) {
+        let mut ret = match res {
+            Ok(ret) => ret,
+            Err(e) => match abi::revert_data_from_error(&e) {
+                Some(revert_data) => ReturnAndReceipt {
+                    revert_reason: Some(abi::decode_revert_data(
+                        &revert_data,
+                        &abis_for_decoding(abi),
+                    )),
+                    ..Default::default()
+                },
+                None => {
+                    backend::send_error_popup(format!("ERROR: {}", e));
+                    record.error = Some(e.to_string());
+                    shared_state::push_tx_record(record);
+                    return;
+                }
+            },
+        };
 
-            log!("{:?}", res);
-            let mut temp_fn_output_write_lock = STATE.temp_fn_output.write().unwrap();
-            let inner_map = temp_fn_output_write_lock
+        if let Some(receipt) = &ret.tx_receipt {
+            let abis = abis_for_decoding(abi);
+            let logs: Vec<DecodedLog> = receipt
+                .logs
+                .iter()
+                .map(|log| DecodedLog::new(log.clone(), &abis))
+                .collect();
+            record.logs = logs.clone();
+            // The history entry keeps everything; the event viewer only this contract's own
+            STATE
+                .temp_receipt_logs
+                .write()
+                .unwrap()
                 .entry(format!("{:#x}", address_h160))
-                .or_insert_with(HashMap::new);
+                .or_default()
+                .extend(
+                    logs.into_iter()
+                        .filter(|decoded| decoded.log.address == address_h160),
+                );
+        }
 
-            match res {
-                Ok(ret) => {
-                    inner_map.insert(
-                        "hope nobody else uses this name for a function".to_string(),
-                        ret,
-                    );
-                }
-                Err(e) => {
-                    backend::send_error_popup(format!("ERROR: {}", e));
-                }
-            }
-        });
+        ret.inputs = inputs;
+        record.result = ret.clone();
+        shared_state::push_tx_record(record);
+
+        STATE
+            .temp_fn_output
+            .write()
+            .unwrap()
+            .entry(format!("{:#x}", address_h160))
+            .or_insert_with(HashMap::new)
+            .insert(key, ret);
     }
 
-    async fn staticcall_raw(address_h160: H160, raw_calldata: String) -> Result<ReturnAndReceipt> {
+    async fn staticcall_raw(
+        address_h160: H160,
+        raw_calldata: String,
+        tx_configs: TxConfigs,
+    ) -> Result<ReturnAndReceipt> {
         // Decode the provided calldata string into ethers Bytes
-
         let calldata_bytes = Bytes::from(
             hex::decode(raw_calldata.strip_prefix("0x").unwrap_or(&raw_calldata))
-                .expect("Error decoding calldata"),
+                .map_err(|e| eyre!("Calldata isn't valid hex: {}", e))?,
         );
 
         let client_wrapper = shared_state::read_shared_client()?;
         let client = client_wrapper.client;
 
-        let tx_configs = shared_state::read_tx_configs();
-
         let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::default());
-
-        // set the fron before we fill so we get the correct nonce
-        tx.set_from(tx_configs.from_address);
-        client.fill_transaction(&mut tx, None).await?;
         tx.set_to(address_h160);
-        tx.set_value(utils::eth_str_to_u256_wei(&tx_configs.value)?);
-        tx.set_gas_price(tx_configs.gas_price);
         tx.set_data(calldata_bytes);
 
+        // set the from (and any overrides) before we fill so we get the correct nonce
+        utils::apply_tx_configs(&mut tx, &tx_configs)?;
+        client.fill_transaction(&mut tx, None).await?;
+
         log!("{:?}", tx);
         let result = client.provider().call_raw(&tx).await?;
         log!("staticcall result: {:?}", result);
@@ -671,12 +1731,148 @@ impl DeployedContract {
         Ok(ReturnAndReceipt {
             return_output: result,
             tx_receipt: None,
+            revert_reason: None,
+            ..Default::default()
         })
     }
 }
 
+// (artifact path, `source:Contract`) of every indexed artifact, for the artifact pickers. The
+// picked one is loaded right away, see backend::query_for_artifact.
+fn artifact_names() -> Vec<(String, String)> {
+    STATE
+        .artifact_index
+        .read()
+        .unwrap()
+        .entries
+        .iter()
+        .map(|entry| (entry.artifact_path.clone(), entry.display_name()))
+        .collect()
+}
+
+// The loaded artifact for a picker's selection, None while it's still loading
+fn picked_artifact(artifact_path: &Option<String>) -> Option<CompiledContract> {
+    STATE
+        .compiled_contracts
+        .read()
+        .unwrap()
+        .get(artifact_path.as_ref()?)
+        .cloned()
+}
+
+// Clicked before the picked artifact came in; asks again in case it was picked in an earlier
+// session or the first load failed
+fn still_loading(artifact_path: &str) {
+    backend::query_for_artifact(artifact_path.to_string());
+    backend::send_error_popup("The artifact is still loading, try again in a moment".to_string());
+}
+
+// View/pure functions that take no inputs, by name
+fn parameterless_views(abi: &Value) -> Vec<&Value> {
+    let mut functions: Vec<&Value> = abi
+        .as_array()
+        .map(|abi_items| {
+            abi_items
+                .iter()
+                .filter(|abi_item| {
+                    abi_item["type"] == "function"
+                        && matches!(abi_item["stateMutability"].as_str(), Some("view" | "pure"))
+                        && abi_item["inputs"]
+                            .as_array()
+                            .map_or(true, |inputs| inputs.is_empty())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    functions.sort_by_key(|abi_item| abi_item["name"].as_str().unwrap_or_default());
+    functions
+}
+
+// The contract's own ABI first, then everything else we know about
+fn abis_for_decoding(abi: &Value) -> Vec<Value> {
+    let mut abis = vec![abi.clone()];
+    abis.extend(shared_state::read_known_abis());
+    abis
+}
+
+// Raw calldata (no 0x) against the contract's ABI, then everything in the selector registry
+fn decode_raw_calldata(name: &str, abi: &Value, raw_calldata: &str) -> Result<DecodedCall> {
+    let calldata = hex::decode(raw_calldata).map_err(|_| eyre!("Calldata isn't valid hex"))?;
+    STATE
+        .selector_registry
+        .read()
+        .unwrap()
+        .decode_calldata(&calldata, Some((name, abi)))
+}
+
+// Same, minus the other deployed contracts since their lock is held while rendering
+fn abis_for_rendering(abi: &Value) -> Vec<Value> {
+    let mut abis = vec![abi.clone()];
+    abis.extend(shared_state::read_artifact_abis());
+    abis
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct ReturnAndReceipt {
     pub tx_receipt: Option<ethers::types::TransactionReceipt>, // Staticalls won't have a receipt
     pub return_output: Bytes,
+    pub revert_reason: Option<String>, // decoded revert data if the call reverted
+    #[serde(default)]
+    pub inputs: HashMap<String, String>, // param name -> input the call was made with
+}
+
+// One eth_getLogs fetch for the events section
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct LogPage {
+    pub logs: Vec<DecodedLog>,
+    pub searched_from: u64, // first block searched, past the deployment if the range was capped
+    pub older: bool,        // before the logs already shown rather than replacing them
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct DecodedLog {
+    pub log: Log,
+    pub decoded: Option<String>, // None if no known ABI has a matching event
+}
+
+impl DecodedLog {
+    pub fn new(log: Log, abis: &[Value]) -> Self {
+        Self {
+            decoded: abi::decode_log(&log, abis),
+            log,
+        }
+    }
+
+    pub fn show(&self, ui: &mut Ui) {
+        let block = self
+            .log
+            .block_number
+            .map(|number| format!("[block {}] ", number))
+            .unwrap_or_default();
+
+        // Fall back to the raw topics + data if we couldn't decode it
+        let text = match &self.decoded {
+            Some(decoded) => format!("{}{}", block, decoded),
+            None => {
+                let topics = self
+                    .log
+                    .topics
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, topic)| format!("topic{}: {:#x}", idx, topic))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                format!(
+                    "{}unknown event from {:#x}\n{}\ndata: {}",
+                    block, self.log.address, topics, self.log.data
+                )
+            }
+        };
+
+        ui.add(
+            egui::TextEdit::multiline(&mut text.as_str())
+                .desired_rows(1)
+                .desired_width(f32::INFINITY),
+        );
+    }
 }
@@ -0,0 +1,131 @@
+use crate::{
+    bytecode,
+    disassembler::{self, DispatcherSelector, Instruction},
+};
+use egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::hash::Hash;
+
+// Disassembly of one or more pieces of code (e.g. runtime + creation), built once when opened
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct DisassemblyView {
+    pub listings: Vec<Listing>,
+    pub selected: usize,
+    pub jumpdests_only: bool,
+    pub filter: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Listing {
+    pub label: String,
+    pub code_size: usize,
+    pub metadata_length: Option<usize>,
+    pub instructions: Vec<Instruction>,
+    pub selectors: Vec<DispatcherSelector>,
+}
+
+impl Listing {
+    pub fn new(label: &str, code: &[u8], abis: &[Value]) -> Self {
+        let instructions = disassembler::disassemble(code);
+        let selectors = disassembler::resolve_selectors(
+            &disassembler::dispatcher_selectors(&instructions),
+            abis,
+        );
+        Self {
+            label: label.to_string(),
+            code_size: code.len(),
+            metadata_length: bytecode::metadata_length(code),
+            instructions,
+            selectors,
+        }
+    }
+}
+
+impl DisassemblyView {
+    pub fn new(codes: &[(&str, &[u8])], abis: &[Value]) -> Self {
+        Self {
+            listings: codes
+                .iter()
+                .map(|(label, code)| Listing::new(label, code, abis))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    // `id_salt` tells apart the scroll areas of different contracts' listings
+    pub fn show(&mut self, ui: &mut Ui, id_salt: impl Hash) {
+        if self.listings.len() > 1 {
+            ui.horizontal(|ui| {
+                for (idx, listing) in self.listings.iter().enumerate() {
+                    ui.radio_value(&mut self.selected, idx, listing.label.clone());
+                }
+            });
+        }
+        let Some(listing) = self.listings.get(self.selected) else {
+            ui.label("No code");
+            return;
+        };
+
+        let jumpdests = listing
+            .instructions
+            .iter()
+            .filter(|instruction| instruction.is_jumpdest())
+            .count();
+        ui.label(format!(
+            "{} bytes, {} instructions, {} JUMPDESTs{}",
+            listing.code_size,
+            listing.instructions.len(),
+            jumpdests,
+            listing
+                .metadata_length
+                .map(|length| format!(", {} bytes of metadata", length))
+                .unwrap_or_default()
+        ));
+
+        ui.collapsing(
+            format!("Dispatcher selectors ({})", listing.selectors.len()),
+            |ui| {
+                for selector in listing.selectors.iter() {
+                    ui.monospace(format!(
+                        "{}  {}",
+                        selector.selector,
+                        selector.signature.as_deref().unwrap_or("unknown")
+                    ));
+                }
+            },
+        );
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.jumpdests_only, "JUMPDESTs only");
+            egui::TextEdit::singleline(&mut self.filter)
+                .hint_text("Filter, e.g. SSTORE")
+                .show(ui);
+        });
+
+        let filter = self.filter.trim().to_uppercase();
+        let shown: Vec<&Instruction> = listing
+            .instructions
+            .iter()
+            .filter(|instruction| !self.jumpdests_only || instruction.is_jumpdest())
+            .filter(|instruction| {
+                filter.is_empty() || instruction.to_string().to_uppercase().contains(&filter)
+            })
+            .collect();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::vertical()
+            .id_source(("disassembly", id_salt, listing.label.clone()))
+            .max_height(300.0)
+            .auto_shrink([false, true])
+            .show_rows(ui, row_height, shown.len(), |ui, row_range| {
+                for instruction in shown[row_range].iter() {
+                    let text = RichText::new(instruction.to_string()).monospace();
+                    match instruction.is_jumpdest() {
+                        true => ui.label(text.color(Color32::LIGHT_BLUE)),
+                        false => ui.label(text),
+                    };
+                }
+            });
+    }
+}
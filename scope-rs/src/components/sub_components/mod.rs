@@ -3,9 +3,11 @@ pub use address_selector::AddressSelector;
 pub mod compiled_contract;
 pub use compiled_contract::CompiledContract;
 pub mod deployed_contract;
-pub use deployed_contract::{DeployedContract, ReturnAndReceipt};
+pub use deployed_contract::{DecodedLog, DeployedContract, LogPage, ReturnAndReceipt};
 pub mod copy_button;
 pub use copy_button::CopyButton;
+pub mod disassembly_view;
+pub use disassembly_view::DisassemblyView;
 pub mod test_list;
 pub use test_list::TestList;
 pub mod target_mode;
@@ -14,3 +16,7 @@ pub mod selected_target;
 pub use selected_target::SelectedTarget;
 pub mod utility_menu;
 pub use utility_menu::UtilityMenu;
+pub mod tx_record;
+pub use tx_record::{TxKind, TxRecord, TxReplay};
+pub mod watch;
+pub use watch::Watch;
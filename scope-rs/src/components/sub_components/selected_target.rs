@@ -5,7 +5,7 @@ use crate::{
     app::RenderConfigs,
     backend,
     components::{CompiledContract, TargetMode, TestList, UtilityMenu},
-    shared_state::STATE,
+    shared_state::{self, STATE},
 };
 use eyre::Result;
 use regex::Regex;
@@ -81,7 +81,9 @@ impl SelectedTarget {
     pub fn new_load_raw() -> Self {
         Self {
             name: "Load Raw".to_string(),
-            mode: TargetMode::LoadRaw {},
+            mode: TargetMode::LoadRaw {
+                code_to_etch: String::new(),
+            },
         }
     }
 
@@ -98,7 +100,11 @@ impl SelectedTarget {
                     ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
                         if ui.button("Deploy").clicked() {
                             if let Some(c) = contract {
-                                backend::deploy_wrapper(c.clone(), constructor_args.clone());
+                                backend::deploy_wrapper(
+                                    c.clone(),
+                                    constructor_args.clone(),
+                                    shared_state::read_tx_configs(),
+                                );
                             }
                         }
                         if let Some(c) = contract {
@@ -122,8 +128,7 @@ impl SelectedTarget {
                                 ui.label("Constructor args:");
                                 for (index, param) in input_array.iter().enumerate() {
                                     let param_name = param["name"].as_str().unwrap_or("unnamed");
-                                    let param_type =
-                                        param["type"].as_str().unwrap_or("Unknown type");
+                                    let param_type = abi::param_type_string(param);
 
                                     // Create an input field for each parameter
                                     let placeholder: String =
@@ -159,6 +164,34 @@ impl SelectedTarget {
                             render_configs.load_address = String::new();
                         }
                     });
+
+                    ui.horizontal_top(|ui| {
+                        // Swap the runtime code at an existing address for this contract's
+                        ui.label("Etch target at: ");
+                        egui::TextEdit::singleline(&mut render_configs.etch_address)
+                            .hint_text("Address".to_string())
+                            .desired_width(ui.available_width() - 52.5) // sorry
+                            .show(ui);
+
+                        if ui.button("Etch").clicked() {
+                            match contract.deployed_bytecode() {
+                                Some(code) => {
+                                    backend::set_code_wrapper(
+                                        render_configs.etch_address.to_string(),
+                                        code,
+                                        Some(contract.clone()),
+                                    );
+                                    render_configs.etch_address = String::new();
+                                }
+                                None => backend::send_error_popup(
+                                    "ERROR: No deployedBytecode in artifact".to_string(),
+                                ),
+                            }
+                        }
+                    });
+                }
+                if let Some(contract) = contract.as_mut() {
+                    ui.collapsing("Disassembly", |ui| contract.show_disassembly(ui));
                 }
             }
 
@@ -182,6 +215,7 @@ impl SelectedTarget {
                     if ui.button("Deploy").clicked() {
                         backend::deploy_raw_bytecode_wrapper(
                             bytecode_to_deploy.trim_start_matches("0x").to_string(),
+                            shared_state::read_tx_configs(),
                         );
                     }
                 });
@@ -190,7 +224,7 @@ impl SelectedTarget {
                     .desired_width(f32::INFINITY);
                 ui.add(text_edit);
             }
-            TargetMode::LoadRaw { .. } => {
+            TargetMode::LoadRaw { code_to_etch } => {
                 ui.label(format!("Target: {:?}", self.name));
                 ui.horizontal(|ui| {
                     ui.horizontal(|ui| {
@@ -200,10 +234,22 @@ impl SelectedTarget {
                             .show(ui);
                         if ui.button("Load").clicked() {
                             backend::load_at_address_wrapper(None, address.to_string());
-                            render_configs.load_address = String::new();
+                            address.clear();
+                        }
+                        if ui.button("Etch").clicked() {
+                            backend::set_code_wrapper(
+                                address.to_string(),
+                                code_to_etch.clone(),
+                                None,
+                            );
+                            address.clear();
                         }
                     });
                 });
+                let text_edit = egui::TextEdit::singleline(code_to_etch)
+                    .hint_text("Runtime bytecode to etch (optional)")
+                    .desired_width(f32::INFINITY);
+                ui.add(text_edit);
             }
         }
     }
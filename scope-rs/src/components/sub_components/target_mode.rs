@@ -23,5 +23,6 @@ pub enum TargetMode {
     LoadRaw {
         // contract_name: String,
         // abi: Value,
+        code_to_etch: String, // runtime code for "Etch", left empty to just load
     },
 }
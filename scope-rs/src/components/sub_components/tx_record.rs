@@ -0,0 +1,199 @@
+use crate::{
+    backend,
+    components::{DecodedLog, DeployedContract, ReturnAndReceipt},
+    shared_state::{TxConfigs, STATE},
+};
+use egui::{epaint::ahash::HashMap, Ui};
+use ethers::types::H160;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TxKind {
+    Deploy,
+    Send,
+    Staticcall,
+}
+
+// Everything needed to send the same call again
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TxReplay {
+    Function {
+        abi_item: Value, // just the called function, so history stays small when persisted
+        selector: String,
+        func_param_input: HashMap<String, HashMap<String, String>>, // selector -> param name -> input
+    },
+    RawCalldata {
+        calldata: String,
+    },
+    Deploy {
+        file_path: String, // looked up in compiled_contracts on rerun
+        constructor_args: Vec<String>,
+    },
+    DeployRaw {
+        bytecode: String,
+    },
+}
+
+// One entry in the session-wide transaction history
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TxRecord {
+    pub kind: TxKind,
+    pub target: H160, // called contract, or the created one for deploys
+    pub target_name: String,
+    pub call: String, // e.g. transfer(to: 0xabc.., amount: 1 ether)
+    pub tx_configs: TxConfigs,
+    pub result: ReturnAndReceipt,
+    pub logs: Vec<DecodedLog>,
+    pub error: Option<String>, // set if the call failed without revert data
+    pub replay: TxReplay,
+}
+
+impl TxRecord {
+    pub fn new(
+        kind: TxKind,
+        target: H160,
+        target_name: String,
+        call: String,
+        tx_configs: TxConfigs,
+        replay: TxReplay,
+    ) -> Self {
+        Self {
+            kind,
+            target,
+            target_name,
+            call,
+            tx_configs,
+            result: ReturnAndReceipt::default(),
+            logs: Vec::new(),
+            error: None,
+            replay,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("failed: {}", error);
+        }
+        if let Some(revert_reason) = &self.result.revert_reason {
+            return format!("reverted: {}", revert_reason);
+        }
+        match self.result.tx_receipt.as_ref().and_then(|r| r.status) {
+            Some(status) if status.is_zero() => "reverted".to_string(),
+            _ => "success".to_string(),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let kind = match self.kind {
+            TxKind::Deploy => "deploy",
+            TxKind::Send => "send",
+            TxKind::Staticcall => "call",
+        };
+        format!(
+            "{} {}.{} - {}",
+            kind,
+            self.target_name,
+            self.call,
+            self.status()
+        )
+    }
+
+    pub fn show(&self, ui: &mut Ui) {
+        let value = match self.tx_configs.value.is_empty() {
+            true => "0".to_string(),
+            false => self.tx_configs.value.clone(),
+        };
+        let mut details = vec![
+            format!("target: {} ({:#x})", self.target_name, self.target),
+            format!("call: {}", self.call),
+            format!("from: {:#x}", self.tx_configs.from_address),
+            format!("value: {} ether", value),
+            format!("type: {}", self.tx_configs.tx_type),
+            format!("status: {}", self.status()),
+        ];
+
+        if let Some(receipt) = &self.result.tx_receipt {
+            details.push(format!("tx hash: {:#x}", receipt.transaction_hash));
+            if let Some(block_number) = receipt.block_number {
+                details.push(format!("block: {}", block_number));
+            }
+            if let Some(gas_used) = receipt.gas_used {
+                details.push(format!("gas used: {}", gas_used));
+            }
+            if let Some(gas_price) = receipt.effective_gas_price {
+                details.push(format!("effective gas price: {} wei", gas_price));
+            }
+        }
+        if !self.result.return_output.is_empty() {
+            details.push(format!("return: {}", self.result.return_output));
+        }
+
+        ui.add(
+            egui::TextEdit::multiline(&mut details.join("\n").as_str())
+                .desired_rows(1)
+                .desired_width(f32::INFINITY),
+        );
+
+        if !self.logs.is_empty() {
+            ui.label("Logs:");
+            for log in self.logs.iter() {
+                log.show(ui);
+            }
+        }
+    }
+
+    // Sends the same call again with the same configs it was originally sent with
+    pub fn rerun(&self) {
+        // ...except the nonce, which has been used up
+        let mut tx_configs = self.tx_configs.clone();
+        tx_configs.nonce.clear();
+
+        match &self.replay {
+            TxReplay::Function {
+                abi_item,
+                selector,
+                func_param_input,
+            } => DeployedContract::spawn_function_call(
+                self.kind,
+                self.target,
+                self.target_name.clone(),
+                Value::Array(vec![abi_item.clone()]),
+                selector.clone(),
+                func_param_input.clone(),
+                tx_configs,
+            ),
+            TxReplay::RawCalldata { calldata } => DeployedContract::spawn_raw_call(
+                self.kind,
+                self.target,
+                self.target_name.clone(),
+                Value::Array(vec![]),
+                calldata.clone(),
+                tx_configs,
+            ),
+            TxReplay::Deploy {
+                file_path,
+                constructor_args,
+            } => {
+                let compiled = STATE
+                    .compiled_contracts
+                    .read()
+                    .unwrap()
+                    .get(file_path)
+                    .cloned();
+                match compiled {
+                    Some(compiled) => {
+                        backend::deploy_wrapper(compiled, constructor_args.clone(), tx_configs)
+                    }
+                    None => backend::send_error_popup(format!(
+                        "ERROR: No compiled artifact for {}, select and compile it first",
+                        file_path
+                    )),
+                }
+            }
+            TxReplay::DeployRaw { bytecode } => {
+                backend::deploy_raw_bytecode_wrapper(bytecode.clone(), tx_configs)
+            }
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
-    abi, backend,
-    components::{CompiledContract, DeployedContract},
+    abi, backend, bytecode,
+    components::{CompiledContract, CopyButton, DeployedContract, Watch},
     shared_state,
 };
-use egui::Ui;
+use egui::{Color32, Ui};
 use ethers::etherscan::contract;
 use eyre::Result;
 
@@ -115,33 +115,125 @@ impl UtilityMenu {
         });
     }
 
-    pub fn show_for_deployed(ui: &mut Ui, contract: &DeployedContract) -> Result<()> {
+    pub fn show_for_deployed(ui: &mut Ui, contract: &mut DeployedContract) -> Result<()> {
         ui.menu_button("🔨", |ui| {
-            if ui.button("Get Deployed Bytecode").clicked() {
-                backend::send_shell_command(format!("cast code {}", contract.address_string));
-            }
+            ui.menu_button("Bytecode", |ui| Self::show_bytecode_info(ui, contract));
 
             if ui.button("Get Balance").clicked() {
                 backend::send_shell_command(format!("cast balance {}", contract.address_string));
             }
+
+            if ui.button("Get Nonce").clicked() {
+                backend::get_nonce(contract.address_h160);
+                ui.close_menu();
+            }
+
+            ui.horizontal(|ui| {
+                egui::TextEdit::singleline(&mut contract.nonce_input)
+                    .hint_text("nonce")
+                    .desired_width(80.0)
+                    .show(ui);
+                if ui.button("Set Nonce").clicked() {
+                    match contract.nonce_input.trim().parse::<u64>() {
+                        Ok(nonce) => {
+                            backend::set_nonce(contract.address_h160, nonce.into());
+                            contract.nonce_input.clear();
+                            ui.close_menu();
+                        }
+                        Err(_) => backend::send_error_popup("Failed to parse nonce".to_string()),
+                    }
+                }
+            });
         });
 
         Ok(())
     }
 
+    // Sizes against the deployment limits + whatever solc left in the metadata tail
+    fn show_bytecode_info(ui: &mut Ui, contract: &DeployedContract) {
+        let size_label = |ui: &mut Ui, name: &str, size: usize, limit: usize| {
+            let text = format!("{} size: {} / {} bytes", name, size, limit);
+            match size > limit {
+                true => ui.colored_label(Color32::RED, text),
+                false => ui.label(text),
+            };
+        };
+        size_label(
+            ui,
+            "Code",
+            contract.bytecode_b.len(),
+            bytecode::MAX_CODE_SIZE,
+        );
+        // Creation code from the artifact, so without any constructor args
+        if let Some(compiled) = contract.compiled_contract.as_ref() {
+            size_label(
+                ui,
+                "Initcode",
+                compiled.bytecode_string.len() / 2,
+                bytecode::MAX_INITCODE_SIZE,
+            );
+        }
+
+        ui.separator();
+        match bytecode::decode_metadata(&contract.bytecode_b) {
+            Some(metadata) => {
+                ui.label(format!(
+                    "solc: {}",
+                    metadata.solc.as_deref().unwrap_or("unknown")
+                ));
+                for (name, hash) in [
+                    ("ipfs", &metadata.ipfs),
+                    ("bzzr0", &metadata.bzzr0),
+                    ("bzzr1", &metadata.bzzr1),
+                ] {
+                    if let Some(hash) = hash {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{}: {}", name, hash));
+                            CopyButton::new("📋".to_string(), hash.clone()).show(ui);
+                        });
+                    }
+                }
+                if metadata.experimental {
+                    ui.colored_label(Color32::YELLOW, "Uses experimental features");
+                }
+                if !metadata.other.is_empty() {
+                    ui.label(format!("Other metadata: {}", metadata.other.join(", ")));
+                }
+            }
+            None => {
+                ui.label("No metadata");
+            }
+        }
+
+        ui.separator();
+        CopyButton::new(
+            "Copy Runtime Code".to_string(),
+            format!("0x{}", contract.bytecode_string),
+        )
+        .show(ui);
+    }
+
     pub fn show_for_function(
         ui: &mut Ui,
         contract: &DeployedContract,
-        abi: serde_json::Value,
+        abi: &serde_json::Value,
         abi_item: &&serde_json::Value,
-        func_name: String,
+        selector: String,
     ) {
         ui.menu_button("🔨", |ui| {
+            // Re-run on every block with the current inputs, see the Watches section
+            if matches!(abi_item["stateMutability"].as_str(), Some("view" | "pure"))
+                && ui.button("Watch").clicked()
+            {
+                Watch::pin(Watch::new(contract, abi_item, selector.clone()));
+                ui.close_menu();
+            }
+
             // idk if the same metadata will always be enabled? better way to handle errors than nested .gets?
             if ui.button("Copy raw calldata").clicked() {
                 match abi::encode_fn_call_to_calldata(
-                    func_name.to_string(),
-                    &abi,
+                    selector.clone(),
+                    abi,
                     &contract.func_param_input,
                 ) {
                     Ok(calldata) => ui.output_mut(|o| o.copied_text = calldata),
@@ -149,7 +241,7 @@ impl UtilityMenu {
                 }
                 ui.close_menu();
             }
-            if let Some(ret) = contract.func_output.get(&func_name) {
+            if let Some(ret) = contract.func_output.get(&selector) {
                 if ui.button("Copy raw return").clicked() {
                     ui.output_mut(|o| o.copied_text = format!("{}", ret.return_output));
 
@@ -159,8 +251,8 @@ impl UtilityMenu {
 
             if ui.button("Trace").clicked() {
                 match abi::encode_fn_call_to_calldata(
-                    func_name.to_string(),
-                    &abi,
+                    selector.clone(),
+                    abi,
                     &contract.func_param_input.clone(),
                 ) {
                     Ok(calldata) => {
@@ -180,8 +272,8 @@ impl UtilityMenu {
                 match abi_item["stateMutability"].as_str() {
                     Some("view") | Some("pure") => {
                         if let Ok(calldata) = abi::encode_fn_call_to_calldata(
-                            func_name.to_string(),
-                            &abi,
+                            selector.clone(),
+                            abi,
                             &contract.func_param_input.clone(),
                         ) {
                             log!("calldata: {:?}", calldata);
@@ -192,7 +284,7 @@ impl UtilityMenu {
                         }
                     }
                     _ => {
-                        if let Some(ret) = contract.func_output.get(&func_name) {
+                        if let Some(ret) = contract.func_output.get(&selector) {
                             if let Some(receipt) = ret.tx_receipt.clone() {
                                 backend::send_shell_command(format!(
                                     "cast run --debug {:?}",
@@ -0,0 +1,169 @@
+use crate::{
+    abi,
+    components::DeployedContract,
+    shared_state::{self, STATE},
+};
+use egui::{epaint::ahash::HashMap, Color32, Ui};
+use ethers::{abi::Token, types::H160};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// How long a changed value stays highlighted (ms)
+const HIGHLIGHT_DURATION: f64 = 5000.0;
+
+// A pinned view/pure call, re-run on every new block (or on the dashboard's interval)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Watch {
+    pub address: H160,
+    pub contract_name: String,
+    pub abi_item: Value, // just the watched function
+    pub selector: String,
+    pub inputs: HashMap<String, String>, // param name -> input, fixed when pinned
+    pub previous: Option<String>,
+    pub current: Option<String>,
+    pub changed_at: Option<f64>, // js time of the last change, for highlighting
+}
+
+impl Watch {
+    pub fn new(contract: &DeployedContract, abi_item: &Value, selector: String) -> Self {
+        Self {
+            address: contract.address_h160,
+            contract_name: contract.name.clone(),
+            abi_item: abi_item.clone(),
+            inputs: contract
+                .func_param_input
+                .get(&selector)
+                .cloned()
+                .unwrap_or_default(),
+            selector,
+            previous: None,
+            current: None,
+            changed_at: None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{}.{}",
+            self.contract_name,
+            abi::describe_fn_call(&self.abi_item, &self.inputs)
+        )
+    }
+
+    // Pinning the same call twice just keeps the first one
+    pub fn pin(watch: Watch) {
+        let mut watches = STATE.watches.write().unwrap();
+        let is_duplicate = watches.iter().any(|w| {
+            w.address == watch.address && w.selector == watch.selector && w.inputs == watch.inputs
+        });
+        if !is_duplicate {
+            watches.push(watch);
+        }
+        drop(watches);
+        Watch::refresh_all();
+    }
+
+    pub fn refresh_all() {
+        let watches = STATE.watches.read().unwrap().clone();
+        for (idx, watch) in watches.into_iter().enumerate() {
+            watch.refresh(idx);
+        }
+    }
+
+    fn refresh(self, idx: usize) {
+        let abi = Value::Array(vec![self.abi_item.clone()]);
+        let mut func_param_input = HashMap::default();
+        func_param_input.insert(self.selector.clone(), self.inputs.clone());
+        let tx_configs = shared_state::read_tx_configs();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let value = match DeployedContract::staticcall(
+                self.address,
+                abi.clone(),
+                self.selector.clone(),
+                func_param_input,
+                tx_configs,
+            )
+            .await
+            {
+                Ok(ret) => {
+                    match abi::decode_return_values(self.selector.clone(), &abi, &ret.return_output)
+                    {
+                        Ok(tokens) => format_values(&tokens),
+                        Err(e) => format!("decode error: {}", e),
+                    }
+                }
+                Err(e) => match abi::revert_data_from_error(&e) {
+                    Some(revert_data) => format!(
+                        "reverted: {}",
+                        abi::decode_revert_data(&revert_data, &shared_state::read_known_abis())
+                    ),
+                    None => format!("error: {}", e),
+                },
+            };
+
+            // The list may have changed while the call was in flight
+            let mut watches = STATE.watches.write().unwrap();
+            let Some(watch) = watches.get_mut(idx) else {
+                return;
+            };
+            if watch.address != self.address
+                || watch.selector != self.selector
+                || watch.inputs != self.inputs
+            {
+                return;
+            }
+            if watch.current.as_ref() != Some(&value) {
+                if watch.current.is_some() {
+                    watch.changed_at = Some(js_sys::Date::now());
+                }
+                watch.previous = watch.current.replace(value);
+            }
+        });
+    }
+
+    // Returns true if the watch should be removed
+    pub fn show(&self, ui: &mut Ui) -> bool {
+        let mut should_remove = false;
+        let changed_recently = self
+            .changed_at
+            .is_some_and(|changed_at| js_sys::Date::now() - changed_at < HIGHLIGHT_DURATION);
+
+        ui.horizontal(|ui| {
+            if ui.button("❌").clicked() {
+                should_remove = true;
+            }
+            ui.label(self.describe())
+                .on_hover_text(format!("{:#x}", self.address));
+        });
+
+        let current = self.current.clone().unwrap_or_else(|| "-".to_string());
+        ui.horizontal(|ui| {
+            ui.label("  now: ");
+            match changed_recently {
+                true => ui.colored_label(Color32::YELLOW, current),
+                false => ui.label(current),
+            };
+        });
+        if let Some(previous) = &self.previous {
+            ui.horizontal(|ui| {
+                ui.label("  was: ");
+                ui.label(previous);
+            });
+        }
+
+        should_remove
+    }
+}
+
+// Uints in decimal, since these are mostly balances/supplies/etc
+fn format_values(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Uint(value) => value.to_string(),
+            token => abi::format_token(token),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -1,4 +1,7 @@
-use crate::{app::RenderConfigs, shared_state::STATE};
+use crate::{
+    app::RenderConfigs,
+    shared_state::{TxType, STATE},
+};
 
 use super::AddressSelector;
 
@@ -12,6 +15,7 @@ impl TxConfigSection {
         ui.label("Transaction Configs");
         self.address_selector.show(ui, render_configs);
         self.render_value_input(ui);
+        self.render_gas_inputs(ui);
     }
 
     fn render_value_input(&mut self, ui: &mut egui::Ui) {
@@ -27,4 +31,66 @@ impl TxConfigSection {
             );
         });
     }
+
+    // Empty fields are left for the node to fill in (estimate, pending nonce, etc)
+    fn render_gas_inputs(&mut self, ui: &mut egui::Ui) {
+        let mut tx_configs = STATE.tx_configs.write().unwrap();
+
+        ui.collapsing("Gas & Nonce", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Tx type:     ");
+                egui::ComboBox::from_id_source("tx_type")
+                    .selected_text(tx_configs.tx_type.to_string())
+                    .width(ui.available_width() * 0.5)
+                    .show_ui(ui, |ui| {
+                        for tx_type in [TxType::Legacy, TxType::Eip2930, TxType::Eip1559] {
+                            ui.selectable_value(
+                                &mut tx_configs.tx_type,
+                                tx_type,
+                                tx_type.to_string(),
+                            );
+                        }
+                    });
+            });
+
+            match tx_configs.tx_type {
+                TxType::Eip1559 => {
+                    render_config_input(
+                        ui,
+                        "Max fee:     ",
+                        &mut tx_configs.max_fee_per_gas,
+                        "auto (wei, or e.g. 2 gwei)",
+                    );
+                    render_config_input(
+                        ui,
+                        "Priority fee:",
+                        &mut tx_configs.max_priority_fee_per_gas,
+                        "auto (wei, or e.g. 1 gwei)",
+                    );
+                }
+                _ => {
+                    render_config_input(
+                        ui,
+                        "Gas price:   ",
+                        &mut tx_configs.gas_price,
+                        "auto (wei, or e.g. 1 gwei)",
+                    );
+                }
+            }
+
+            render_config_input(ui, "Gas limit:   ", &mut tx_configs.gas_limit, "estimate");
+            render_config_input(ui, "Nonce:       ", &mut tx_configs.nonce, "pending nonce");
+        });
+    }
+}
+
+fn render_config_input(ui: &mut egui::Ui, label: &str, value: &mut String, hint: &str) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(
+            egui::TextEdit::singleline(value)
+                .hint_text(hint)
+                .desired_width(ui.available_width() * 0.5),
+        );
+    });
 }
@@ -0,0 +1,65 @@
+use crate::{components::Watch, shared_state::STATE, utils};
+
+// Used when there's no ws subscription to refresh on new blocks (ms)
+const DEFAULT_REFRESH_INTERVAL: f64 = 5000.0;
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct WatchSection {
+    interval_input: String,
+
+    #[serde(skip)]
+    last_refresh: f64,
+}
+
+impl WatchSection {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        ui.collapsing("Watches", |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Refresh").clicked() {
+                    Watch::refresh_all();
+                }
+                if ui.button("Clear").clicked() {
+                    STATE.watches.write().unwrap().clear();
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.interval_input)
+                        .hint_text("refresh every (5s, or each block if live)")
+                        .desired_width(ui.available_width()),
+                );
+            });
+
+            let mut to_remove: Option<usize> = None;
+            for (idx, watch) in STATE.watches.read().unwrap().iter().enumerate() {
+                if watch.show(ui) {
+                    to_remove = Some(idx);
+                }
+            }
+            if let Some(idx) = to_remove {
+                STATE.watches.write().unwrap().remove(idx);
+            }
+        });
+    }
+
+    // While live, the backend refreshes watches on every new block instead
+    fn poll(&mut self) {
+        if *STATE.live_updates.read().unwrap() || STATE.watches.read().unwrap().is_empty() {
+            return;
+        }
+
+        let interval = match self.interval_input.trim() {
+            "" => DEFAULT_REFRESH_INTERVAL,
+            input => match utils::parse_duration_secs(input) {
+                Ok(seconds) => (seconds.max(1) * 1000) as f64,
+                Err(_) => DEFAULT_REFRESH_INTERVAL,
+            },
+        };
+
+        let now = js_sys::Date::now();
+        if now - self.last_refresh > interval {
+            Watch::refresh_all();
+            self.last_refresh = now;
+        }
+    }
+}
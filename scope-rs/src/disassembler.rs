@@ -0,0 +1,348 @@
+use crate::{abi, bytecode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Instruction {
+    pub offset: usize,
+    pub opcode: u8,
+    pub push_data: Vec<u8>, // immediate for PUSHn, may be cut short at the end of the code
+}
+
+impl Instruction {
+    pub fn name(&self) -> String {
+        match opcode_name(self.opcode) {
+            Some(name) => name.to_string(),
+            None => format!("INVALID(0x{:02x})", self.opcode),
+        }
+    }
+
+    pub fn is_jumpdest(&self) -> bool {
+        self.opcode == JUMPDEST
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#06x}  {}", self.offset, self.name())?;
+        if !self.push_data.is_empty() {
+            write!(f, " 0x{}", hex::encode(&self.push_data))?;
+        }
+        Ok(())
+    }
+}
+
+// A selector the dispatcher compares calldata against, with its signature if a known ABI has it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DispatcherSelector {
+    pub selector: String, // 0x-prefixed
+    pub signature: Option<String>,
+}
+
+const JUMPDEST: u8 = 0x5b;
+const PUSH1: u8 = 0x60;
+const PUSH4: u8 = 0x63;
+const DUP1: u8 = 0x80;
+const EQ: u8 = 0x14;
+const XOR: u8 = 0x18;
+
+// Linear sweep over the code, leaving out the metadata tail (which isn't code)
+pub fn disassemble(code: &[u8]) -> Vec<Instruction> {
+    let code = bytecode::strip_metadata(code);
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < code.len() {
+        let opcode = code[offset];
+        let push_length = match opcode {
+            0x60..=0x7f => (opcode - 0x5f) as usize,
+            _ => 0,
+        };
+        let data_end = (offset + 1 + push_length).min(code.len());
+        instructions.push(Instruction {
+            offset,
+            opcode,
+            push_data: code[offset + 1..data_end].to_vec(),
+        });
+        offset += 1 + push_length;
+    }
+    instructions
+}
+
+// PUSH1-4s compared against calldata with EQ (solc, `DUP1 PUSH4 sel EQ`) or XOR (vyper) right
+// after, in the order the dispatcher checks them. Selectors with leading zero bytes get a
+// shorter push, so those are left-padded back to 4 bytes; since small constants are compared
+// all the time, they also need the DUP1 solc puts in front. Only a heuristic, any other
+// constant compared the same way shows up too.
+pub fn dispatcher_selectors(instructions: &[Instruction]) -> Vec<[u8; 4]> {
+    let mut selectors: Vec<[u8; 4]> = Vec::new();
+    for (idx, instruction) in instructions.iter().enumerate() {
+        if !(PUSH1..=PUSH4).contains(&instruction.opcode) {
+            continue;
+        }
+        let push_length = (instruction.opcode - PUSH1 + 1) as usize;
+        // cut short at the end of the code
+        if instruction.push_data.len() != push_length {
+            continue;
+        }
+        if instruction.opcode != PUSH4 && (idx == 0 || instructions[idx - 1].opcode != DUP1) {
+            continue;
+        }
+        let mut selector = [0u8; 4];
+        selector[4 - push_length..].copy_from_slice(&instruction.push_data);
+
+        let compared = instructions
+            .iter()
+            .skip(idx + 1)
+            .take(2)
+            .any(|next| next.opcode == EQ || next.opcode == XOR);
+        if compared && selector != [0xff; 4] && !selectors.contains(&selector) {
+            selectors.push(selector);
+        }
+    }
+    selectors
+}
+
+// Signatures for the selectors from the first ABI that has each one
+pub fn resolve_selectors(selectors: &[[u8; 4]], abis: &[Value]) -> Vec<DispatcherSelector> {
+    selectors
+        .iter()
+        .map(|selector| {
+            let selector = format!("0x{}", hex::encode(selector));
+            let signature = abis
+                .iter()
+                .find_map(|abi| abi::function_item_by_selector(abi, &selector))
+                .map(abi::function_signature);
+            DispatcherSelector {
+                selector,
+                signature,
+            }
+        })
+        .collect()
+}
+
+pub fn opcode_name(opcode: u8) -> Option<&'static str> {
+    let name = match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "KECCAK256",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "PREVRANDAO",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x49 => "BLOBHASH",
+        0x4a => "BLOBBASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x5c => "TLOAD",
+        0x5d => "TSTORE",
+        0x5e => "MCOPY",
+        0x5f => "PUSH0",
+        0x60 => "PUSH1",
+        0x61 => "PUSH2",
+        0x62 => "PUSH3",
+        0x63 => "PUSH4",
+        0x64 => "PUSH5",
+        0x65 => "PUSH6",
+        0x66 => "PUSH7",
+        0x67 => "PUSH8",
+        0x68 => "PUSH9",
+        0x69 => "PUSH10",
+        0x6a => "PUSH11",
+        0x6b => "PUSH12",
+        0x6c => "PUSH13",
+        0x6d => "PUSH14",
+        0x6e => "PUSH15",
+        0x6f => "PUSH16",
+        0x70 => "PUSH17",
+        0x71 => "PUSH18",
+        0x72 => "PUSH19",
+        0x73 => "PUSH20",
+        0x74 => "PUSH21",
+        0x75 => "PUSH22",
+        0x76 => "PUSH23",
+        0x77 => "PUSH24",
+        0x78 => "PUSH25",
+        0x79 => "PUSH26",
+        0x7a => "PUSH27",
+        0x7b => "PUSH28",
+        0x7c => "PUSH29",
+        0x7d => "PUSH30",
+        0x7e => "PUSH31",
+        0x7f => "PUSH32",
+        0x80 => "DUP1",
+        0x81 => "DUP2",
+        0x82 => "DUP3",
+        0x83 => "DUP4",
+        0x84 => "DUP5",
+        0x85 => "DUP6",
+        0x86 => "DUP7",
+        0x87 => "DUP8",
+        0x88 => "DUP9",
+        0x89 => "DUP10",
+        0x8a => "DUP11",
+        0x8b => "DUP12",
+        0x8c => "DUP13",
+        0x8d => "DUP14",
+        0x8e => "DUP15",
+        0x8f => "DUP16",
+        0x90 => "SWAP1",
+        0x91 => "SWAP2",
+        0x92 => "SWAP3",
+        0x93 => "SWAP4",
+        0x94 => "SWAP5",
+        0x95 => "SWAP6",
+        0x96 => "SWAP7",
+        0x97 => "SWAP8",
+        0x98 => "SWAP9",
+        0x99 => "SWAP10",
+        0x9a => "SWAP11",
+        0x9b => "SWAP12",
+        0x9c => "SWAP13",
+        0x9d => "SWAP14",
+        0x9e => "SWAP15",
+        0x9f => "SWAP16",
+        0xa0 => "LOG0",
+        0xa1 => "LOG1",
+        0xa2 => "LOG2",
+        0xa3 => "LOG3",
+        0xa4 => "LOG4",
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selectors(code_hex: &str) -> Vec<String> {
+        dispatcher_selectors(&disassemble(&hex::decode(code_hex).unwrap()))
+            .iter()
+            .map(hex::encode)
+            .collect()
+    }
+
+    #[test]
+    fn solc_dispatcher() {
+        // solc 0.8 Storage: callvalue check, then `DUP1 PUSH4 sel EQ PUSH2 dest JUMPI` per function
+        let code = concat!(
+            "6080604052348015600e575f80fd5b50",
+            "600436106030575f3560e01c8063",
+            "2e64cec1146034578063",
+            "6057361d14604c57",
+            "5b5f80fd5b"
+        );
+        assert_eq!(selectors(code), vec!["2e64cec1", "6057361d"]);
+    }
+
+    #[test]
+    fn leading_zero_selector() {
+        // balanceOf(address,uint256) is 0x00fdd58e, which solc pushes with PUSH3
+        let code = concat!(
+            "5f3560e01c80",
+            "62fdd58e14603157",
+            "80630e89341c14604657",
+            "5b5f80fd"
+        );
+        assert_eq!(selectors(code), vec!["00fdd58e", "0e89341c"]);
+
+        // a PUSH3 without the DUP1 is just some constant
+        assert!(selectors("5f3562fdd58e14603157").is_empty());
+    }
+
+    #[test]
+    fn vyper_xor_dispatcher() {
+        // `PUSH4 sel DUP2 XOR PUSH2 next JUMPI`, falling through on a match
+        let code = concat!(
+            "5f3560e01c",
+            "63a9059cbb811861002057",
+            "5b63095ea7b381186100405757",
+            "5b",
+            "5f80fd"
+        );
+        assert_eq!(selectors(code), vec!["a9059cbb", "095ea7b3"]);
+    }
+
+    #[test]
+    fn pushes_that_arent_compared() {
+        // Panic(uint256) selector written to memory, a mask, and a PUSH4 cut short by the end
+        let code = concat!(
+            "634e487b7160e01b5f52",
+            "63ffffffff16",
+            "63ffffffff8114",
+            "8063aabb"
+        );
+        assert!(selectors(code).is_empty());
+
+        // the same selector compared twice shows up once
+        assert_eq!(
+            selectors("80632e64cec11460345780632e64cec1146034575b"),
+            vec!["2e64cec1"]
+        );
+    }
+}
@@ -10,8 +10,17 @@ mod app;
 pub use app::Frontend;
 
 pub mod abi;
+pub mod artifacts;
 pub mod backend;
+pub mod bytecode;
+pub mod disassembler;
+pub mod multicall;
 pub mod providers;
+pub mod proxy;
+pub mod scrypt;
+pub mod selectors;
+pub mod signers;
+pub mod storage;
 pub mod utils;
 pub mod wasm;
 // pub use abi;
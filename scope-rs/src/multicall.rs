@@ -0,0 +1,61 @@
+use ethers::{
+    abi::{ParamType, Token},
+    types::{Bytes, H160},
+};
+use eyre::{eyre, Result};
+
+// Same address on every chain Multicall3 is deployed to (and where forks will have it)
+pub use ethers::contract::MULTICALL_ADDRESS;
+
+// aggregate3((address,bool,bytes)[])
+const AGGREGATE3_SELECTOR: [u8; 4] = [0x82, 0xad, 0x56, 0xcb];
+
+// Minimal runtime that only implements aggregate3, etched when there's no Multicall3 on the
+// chain and no compiled one in the project. Each call is made with no value and all the
+// remaining gas; the results are abi encoded as (bool success, bytes returnData)[] from 0x100
+// up, with the loop counters kept in scratch memory below that. Like the real one it reverts
+// if a call with allowFailure = false fails.
+pub const FALLBACK_MULTICALL3_CODE: &str = "0x60003560e01c6382ad56cb1461001457600080fd5b600435600401803560005260200160205260006040526020610100526000516101205260005160051b610140016060525b6000516040511461010c576020518060405160051b01350160805260805180604001350160a05260a0513560c05260c05160a051602001606051606001376000600060c0516060516060016000608051355af18060e05260805160200135176100ad57600080fd5b6101406060510360405160051b610140015260e051606051526040606051602001523d606051604001523d60006060516060013e60003d6060510160600152601f3d01601f191660605101606001606052604051600101604052610045565b61010060605103610100f3";
+
+// Calldata for aggregate3, with every call allowed to fail so one revert doesn't sink the rest
+pub fn encode_aggregate3(calls: &[(H160, Bytes)]) -> Bytes {
+    let calls = calls
+        .iter()
+        .map(|(target, calldata)| {
+            Token::Tuple(vec![
+                Token::Address(*target),
+                Token::Bool(true),
+                Token::Bytes(calldata.to_vec()),
+            ])
+        })
+        .collect();
+
+    let mut encoded = AGGREGATE3_SELECTOR.to_vec();
+    encoded.extend(ethers::abi::encode(&[Token::Array(calls)]));
+    Bytes::from(encoded)
+}
+
+// (success, return data) for each call, in order
+pub fn decode_aggregate3(return_data: &Bytes) -> Result<Vec<(bool, Bytes)>> {
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Bool,
+        ParamType::Bytes,
+    ])));
+    let tokens = ethers::abi::decode(&[result_type], return_data)?;
+
+    let Some(Token::Array(results)) = tokens.into_iter().next() else {
+        return Err(eyre!("Unexpected aggregate3 return data"));
+    };
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match &fields[..] {
+                [Token::Bool(success), Token::Bytes(data)] => {
+                    Ok((*success, Bytes::from(data.clone())))
+                }
+                _ => Err(eyre!("Unexpected aggregate3 result: {:?}", fields)),
+            },
+            result => Err(eyre!("Unexpected aggregate3 result: {:?}", result)),
+        })
+        .collect()
+}
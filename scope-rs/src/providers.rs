@@ -1,5 +1,6 @@
-use crate::utils;
+use crate::shared_state::STATE;
 use ethers::{
+    core::rand::thread_rng,
     prelude::{LocalWallet, Provider, SignerMiddleware},
     providers::{Http, Middleware, ProviderExt},
     signers::Signer,
@@ -18,13 +19,15 @@ pub struct ClientProviderWrapper {
 impl ClientProviderWrapper {
     pub async fn new(endpoint: String) -> Result<Self, Box<dyn std::error::Error>> {
         // Connect to the network
-        let provider = Provider::<Http>::connect(&endpoint).await;
+        let provider = Provider::<Http>::try_connect(&endpoint).await?;
 
         // Get chain id
         let chain_id = provider.get_chainid().await?;
 
-        // Instantiate the wallet
-        let wallet: LocalWallet = utils::key(0).with_chain_id(chain_id.as_u64());
+        // Throwaway wallet that never matches a "from" address, so anything not sent through
+        // `client_for` with an imported key goes to the node unsigned
+        let wallet: LocalWallet =
+            LocalWallet::new(&mut thread_rng()).with_chain_id(chain_id.as_u64());
 
         // Instantiate the client with the wallet
         let client = Arc::new(SignerMiddleware::new(provider, wallet));
@@ -37,6 +40,18 @@ impl ClientProviderWrapper {
         })
     }
 
+    // Client that signs with the imported key for `from` if there is one. Otherwise txs from
+    // it are sent unsigned, for the node to impersonate or sign (see signers::AccountMode)
+    pub fn client_for(&self, from: H160) -> Arc<SignerMiddleware<Provider<Http>, LocalWallet>> {
+        match STATE.signers.read().unwrap().get(&from) {
+            Some(wallet) => Arc::new(SignerMiddleware::new(
+                self.client.provider().clone(),
+                wallet.clone().with_chain_id(self.chain_id.as_u64()),
+            )),
+            None => self.client.clone(),
+        }
+    }
+
     pub fn set_address(&mut self, new_address: String) {
         let address_stripped = new_address.strip_prefix("0x").unwrap_or(&new_address);
         let address_bytes_vec = hex::decode(address_stripped).expect("Decoding failed");
@@ -0,0 +1,161 @@
+use crate::shared_state;
+use ethers::{
+    providers::Middleware,
+    types::{transaction::eip2718::TypedTransaction, Bytes, Eip1559TransactionRequest, H160, H256},
+};
+use serde::{Deserialize, Serialize};
+
+// bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)
+pub const EIP1967_IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+// bytes32(uint256(keccak256("eip1967.proxy.beacon")) - 1)
+pub const EIP1967_BEACON_SLOT: &str =
+    "0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50";
+// bytes32(uint256(keccak256("eip1967.proxy.admin")) - 1)
+pub const EIP1967_ADMIN_SLOT: &str =
+    "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103";
+// keccak256("PROXIABLE")
+pub const EIP1822_PROXIABLE_SLOT: &str =
+    "0xc5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7";
+// keccak256("org.zeppelinos.proxy.implementation"), pre-1967 OpenZeppelin proxies
+pub const ZOS_IMPLEMENTATION_SLOT: &str =
+    "0x7050c9e0f4ca769c69bd3a8ef740bc37934f8e2c036e5a723fd8ee048ed3f8c3";
+// keccak256("org.zeppelinos.proxy.admin")
+pub const ZOS_ADMIN_SLOT: &str =
+    "0x10d6a54a4754c8869d6886b5f5d7fbfa5b4522237ea5c60d11bc4e7a1ff9390b";
+
+// implementation(), on beacons
+const IMPLEMENTATION_SELECTOR: [u8; 4] = [0x5c, 0x60, 0xda, 0x1b];
+
+// EIP-1167 runtime code, with the implementation address in between
+const MINIMAL_CLONE_PREFIX: &str = "363d3d373d3d3d363d73";
+const MINIMAL_CLONE_SUFFIX: &str = "5af43d82803e903d91602b57fd5bf3";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ProxyKind {
+    Transparent,  // EIP-1967 slots with an admin (OpenZeppelin TransparentUpgradeableProxy)
+    Eip1967,      // EIP-1967 implementation slot only, e.g. UUPS
+    Beacon,       // EIP-1967 beacon slot, implementation comes from the beacon
+    Eip1822,      // UUPS with the PROXIABLE slot
+    LegacyZos,    // zeppelinos slots
+    MinimalClone, // EIP-1167, implementation is in the code itself
+}
+
+impl std::fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyKind::Transparent => write!(f, "EIP-1967 transparent"),
+            ProxyKind::Eip1967 => write!(f, "EIP-1967"),
+            ProxyKind::Beacon => write!(f, "EIP-1967 beacon"),
+            ProxyKind::Eip1822 => write!(f, "EIP-1822 UUPS"),
+            ProxyKind::LegacyZos => write!(f, "OpenZeppelin legacy"),
+            ProxyKind::MinimalClone => write!(f, "EIP-1167 minimal clone"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProxyInfo {
+    pub kind: ProxyKind,
+    pub implementation: H160,
+    pub admin: Option<H160>,
+    pub beacon: Option<H160>,
+}
+
+// Checks the code for an EIP-1167 clone, then the well known proxy slots in order of how
+// common they are. Anything that fails to read is treated as not a proxy.
+pub async fn detect(address: H160, code: &Bytes) -> Option<ProxyInfo> {
+    if let Some(implementation) = minimal_clone_implementation(code) {
+        return Some(ProxyInfo {
+            kind: ProxyKind::MinimalClone,
+            implementation,
+            admin: None,
+            beacon: None,
+        });
+    }
+    if code.is_empty() {
+        return None;
+    }
+
+    let admin = read_address_slot(address, EIP1967_ADMIN_SLOT).await;
+    if let Some(implementation) = read_address_slot(address, EIP1967_IMPLEMENTATION_SLOT).await {
+        let kind = match admin {
+            Some(_) => ProxyKind::Transparent,
+            None => ProxyKind::Eip1967,
+        };
+        return Some(ProxyInfo {
+            kind,
+            implementation,
+            admin,
+            beacon: None,
+        });
+    }
+
+    if let Some(beacon) = read_address_slot(address, EIP1967_BEACON_SLOT).await {
+        return Some(ProxyInfo {
+            kind: ProxyKind::Beacon,
+            implementation: beacon_implementation(beacon).await.unwrap_or_default(),
+            admin,
+            beacon: Some(beacon),
+        });
+    }
+
+    if let Some(implementation) = read_address_slot(address, EIP1822_PROXIABLE_SLOT).await {
+        return Some(ProxyInfo {
+            kind: ProxyKind::Eip1822,
+            implementation,
+            admin,
+            beacon: None,
+        });
+    }
+
+    if let Some(implementation) = read_address_slot(address, ZOS_IMPLEMENTATION_SLOT).await {
+        return Some(ProxyInfo {
+            kind: ProxyKind::LegacyZos,
+            implementation,
+            admin: read_address_slot(address, ZOS_ADMIN_SLOT).await,
+            beacon: None,
+        });
+    }
+
+    None
+}
+
+pub fn minimal_clone_implementation(code: &Bytes) -> Option<H160> {
+    let code = hex::encode(code);
+    let implementation = code
+        .strip_prefix(MINIMAL_CLONE_PREFIX)?
+        .strip_suffix(MINIMAL_CLONE_SUFFIX)?;
+    match implementation.len() {
+        40 => implementation.parse().ok(),
+        _ => None,
+    }
+}
+
+// The address in a slot, if it holds one (nonzero, nothing in the upper 12 bytes)
+async fn read_address_slot(address: H160, slot: &str) -> Option<H160> {
+    let client_wrapper = shared_state::read_shared_client().ok()?;
+    let word = client_wrapper
+        .client
+        .get_storage_at(address, slot.parse::<H256>().ok()?, None)
+        .await
+        .ok()?;
+    word_to_address(word.as_bytes())
+}
+
+async fn beacon_implementation(beacon: H160) -> Option<H160> {
+    let client_wrapper = shared_state::read_shared_client().ok()?;
+    let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::default());
+    tx.set_to(beacon);
+    tx.set_data(Bytes::from(IMPLEMENTATION_SELECTOR.to_vec()));
+    let return_data = client_wrapper.client.provider().call_raw(&tx).await.ok()?;
+    word_to_address(return_data.get(..32)?)
+}
+
+fn word_to_address(word: &[u8]) -> Option<H160> {
+    if word.len() != 32 || word[..12].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let address = H160::from_slice(&word[12..]);
+    (!address.is_zero()).then_some(address)
+}
@@ -0,0 +1,189 @@
+use hmac::Hmac;
+use sha2::Sha256;
+
+// scrypt (RFC 7914), done by hand for keystores rather than with the scrypt crate. That one
+// rejects N >= 2^(16r), which r = 1 keystores (the Web3 Secret Storage test vector among them)
+// are, and runs in one go. This one runs a bounded number of ROMix rounds per `step`, so the
+// caller can hand control back to the UI in between.
+pub struct Scrypt {
+    n: usize,
+    r: usize,
+    dklen: usize,
+    password: Vec<u8>,
+    blocks: Vec<u32>, // B, p blocks of 32r little-endian words
+    v: Vec<u32>,      // ROMix scratch, N copies of X
+    x: Vec<u32>,      // the block being mixed
+    block: usize,     // which of the p blocks ROMix is on
+    round: usize,     // 0..2N, across ROMix's fill and mix loops
+}
+
+impl Scrypt {
+    // None for parameters that don't make sense (n has to be a power of 2 above 1) or would
+    // overflow
+    pub fn new(password: &[u8], salt: &[u8], n: u32, r: u32, p: u32, dklen: usize) -> Option<Self> {
+        let (n, r, p) = (n as usize, r as usize, p as usize);
+        if !n.is_power_of_two() || n < 2 || r == 0 || p == 0 {
+            return None;
+        }
+        let block_words = r.checked_mul(32)?;
+        let v_words = n.checked_mul(block_words)?;
+        let mut bytes = vec![0u8; block_words.checked_mul(p)?.checked_mul(4)?];
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(password, salt, 1, &mut bytes);
+
+        Some(Self {
+            n,
+            r,
+            dklen,
+            password: password.to_vec(),
+            blocks: bytes
+                .as_chunks::<4>()
+                .0
+                .iter()
+                .map(|word| u32::from_le_bytes(*word))
+                .collect(),
+            v: vec![0; v_words],
+            x: vec![0; block_words],
+            block: 0,
+            round: 0,
+        })
+    }
+
+    // Runs up to `rounds` ROMix rounds, true once every block is mixed
+    pub fn step(&mut self, rounds: usize) -> bool {
+        let block_words = self.x.len();
+        for _ in 0..rounds {
+            if self.is_done() {
+                break;
+            }
+            let block = self.block * block_words..(self.block + 1) * block_words;
+            if self.round == 0 {
+                self.x.copy_from_slice(&self.blocks[block.clone()]);
+            }
+
+            if self.round < self.n {
+                let v_start = self.round * block_words;
+                self.v[v_start..v_start + block_words].copy_from_slice(&self.x);
+            } else {
+                // Integerify: the first word of the last 64 byte chunk, mod N
+                let j = self.x[block_words - 16] as usize % self.n;
+                let v_start = j * block_words;
+                for (x, v) in self
+                    .x
+                    .iter_mut()
+                    .zip(&self.v[v_start..v_start + block_words])
+                {
+                    *x ^= v;
+                }
+            }
+            block_mix(&mut self.x, self.r);
+
+            self.round += 1;
+            if self.round == 2 * self.n {
+                self.blocks[block].copy_from_slice(&self.x);
+                self.block += 1;
+                self.round = 0;
+            }
+        }
+        self.is_done()
+    }
+
+    fn is_done(&self) -> bool {
+        self.block * self.x.len() == self.blocks.len()
+    }
+
+    // The derived key; only meaningful once `step` has returned true
+    pub fn key(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .blocks
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let mut key = vec![0u8; self.dklen];
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(&self.password, &bytes, 1, &mut key);
+        key
+    }
+}
+
+// BlockMix with Salsa20/8 over the 2r 64 byte chunks of `b`: each output chunk is the
+// previous one xored with the next input chunk and hashed, evens first then odds
+fn block_mix(b: &mut [u32], r: usize) {
+    let mut x: [u32; 16] = b[(2 * r - 1) * 16..].try_into().unwrap();
+    let mut y = vec![0u32; b.len()];
+    for i in 0..2 * r {
+        for (x, b) in x.iter_mut().zip(&b[i * 16..(i + 1) * 16]) {
+            *x ^= b;
+        }
+        salsa20_8(&mut x);
+        let out = (i / 2 + (i % 2) * r) * 16;
+        y[out..out + 16].copy_from_slice(&x);
+    }
+    b.copy_from_slice(&y);
+}
+
+// (a, b, c, d) for the quarter rounds, columns then rows
+const QUARTER_ROUNDS: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [5, 9, 13, 1],
+    [10, 14, 2, 6],
+    [15, 3, 7, 11],
+    [0, 1, 2, 3],
+    [5, 6, 7, 4],
+    [10, 11, 8, 9],
+    [15, 12, 13, 14],
+];
+
+fn salsa20_8(b: &mut [u32; 16]) {
+    let mut x = *b;
+    for _ in 0..4 {
+        for [a, b, c, d] in QUARTER_ROUNDS {
+            x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+            x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+            x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+            x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+        }
+    }
+    for (b, x) in b.iter_mut().zip(x) {
+        *b = b.wrapping_add(x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derive(password: &str, salt: &str, n: u32, r: u32, p: u32, rounds: usize) -> String {
+        let mut scrypt = Scrypt::new(password.as_bytes(), salt.as_bytes(), n, r, p, 64).unwrap();
+        while !scrypt.step(rounds) {}
+        hex::encode(scrypt.key())
+    }
+
+    // RFC 7914 section 12
+    #[test]
+    fn rfc_test_vectors() {
+        assert_eq!(
+            derive("", "", 16, 1, 1, usize::MAX),
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+             fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        );
+        assert_eq!(
+            derive("password", "NaCl", 1024, 8, 16, usize::MAX),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+             2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+    }
+
+    #[test]
+    fn stepping_gives_the_same_key() {
+        let all_at_once = derive("password", "NaCl", 1024, 8, 16, usize::MAX);
+        assert_eq!(derive("password", "NaCl", 1024, 8, 16, 1), all_at_once);
+        assert_eq!(derive("password", "NaCl", 1024, 8, 16, 777), all_at_once);
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert!(Scrypt::new(b"", b"", 1000, 8, 1, 32).is_none());
+        assert!(Scrypt::new(b"", b"", 1, 8, 1, 32).is_none());
+        assert!(Scrypt::new(b"", b"", 16, 0, 1, 32).is_none());
+        assert!(Scrypt::new(b"", b"", 16, 1, 0, 32).is_none());
+    }
+}
@@ -0,0 +1,398 @@
+use crate::{abi, artifacts::ArtifactIndex, components::CompiledContract, shared_state::STATE};
+use egui::epaint::ahash::HashMap;
+use ethers::abi::{Abi, Function};
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Every function we know of, by selector, for decoding raw calldata. Built from the
+// methodIdentifiers of each compiled contract we've received, plus the ABIs of every indexed
+// artifact once those are loaded (interfaces and abstract contracts included).
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SelectorRegistry {
+    pub functions: HashMap<String, Vec<KnownFunction>>, // 0x-prefixed selector -> functions
+    pub generation: u64, // bumped on every rebuild, for anything cached against it
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct KnownFunction {
+    pub contract_name: String,
+    pub signature: String,
+    pub abi_item: Value,
+}
+
+#[derive(Clone, Debug)]
+pub struct DecodedCall {
+    pub function: KnownFunction,
+    pub args: Vec<String>, // `name: value`
+}
+
+impl std::fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}({})",
+            self.function.contract_name,
+            self.function.abi_item["name"].as_str().unwrap_or_default(),
+            self.args.join(", ")
+        )
+    }
+}
+
+impl SelectorRegistry {
+    pub fn build<'a>(
+        compiled_contracts: impl Iterator<Item = &'a CompiledContract>,
+        artifact_index: &ArtifactIndex,
+    ) -> Self {
+        let mut registry = Self::default();
+        for compiled in compiled_contracts {
+            let method_identifiers = compiled.compiled_json["methodIdentifiers"].as_object();
+            match method_identifiers {
+                Some(method_identifiers) => {
+                    for (signature, selector) in method_identifiers {
+                        let abi_item = compiled.abi.as_array().and_then(|abi_items| {
+                            abi_items.iter().find(|abi_item| {
+                                abi_item["type"] == "function"
+                                    && abi::function_signature(abi_item) == *signature
+                            })
+                        });
+                        if let (Some(abi_item), Some(selector)) = (abi_item, selector.as_str()) {
+                            registry.insert(
+                                format!("0x{}", selector.trim_start_matches("0x")),
+                                &compiled.contract_name,
+                                abi_item,
+                            );
+                        }
+                    }
+                }
+                None => registry.insert_abi(&compiled.contract_name, &compiled.abi),
+            }
+        }
+
+        for artifact_code in artifact_index.codes.iter() {
+            if let Some(entry) = artifact_index
+                .entries
+                .iter()
+                .find(|entry| entry.artifact_path == artifact_code.artifact_path)
+            {
+                registry.insert_abi(&entry.contract_name, &artifact_code.abi);
+            }
+        }
+        registry
+    }
+
+    fn insert_abi(&mut self, contract_name: &str, abi: &Value) {
+        for abi_item in abi.as_array().into_iter().flatten() {
+            if abi_item["type"] == "function" {
+                self.insert(abi::function_selector(abi_item), contract_name, abi_item);
+            }
+        }
+    }
+
+    // The same contract shows up as both a compiled contract and an artifact
+    fn insert(&mut self, selector: String, contract_name: &str, abi_item: &Value) {
+        let function = KnownFunction {
+            contract_name: contract_name.to_string(),
+            signature: abi::function_signature(abi_item),
+            abi_item: abi_item.clone(),
+        };
+        let functions = self.functions.entry(selector).or_default();
+        if !functions.contains(&function) {
+            functions.push(function);
+        }
+    }
+
+    pub fn lookup(&self, selector: &str) -> &[KnownFunction] {
+        self.functions
+            .get(selector)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // Tries the contract's own ABI first, then every known function with the selector until
+    // one decodes (selectors can collide, args rarely fit both)
+    pub fn decode_calldata(
+        &self,
+        calldata: &[u8],
+        own: Option<(&str, &Value)>,
+    ) -> Result<DecodedCall> {
+        if calldata.len() < 4 {
+            return Err(eyre!("Calldata is shorter than a selector"));
+        }
+        let selector = format!("0x{}", hex::encode(&calldata[..4]));
+
+        let mut candidates: Vec<KnownFunction> = own
+            .and_then(|(contract_name, abi)| {
+                let abi_item = abi::function_item_by_selector(abi, &selector)?;
+                Some(KnownFunction {
+                    contract_name: contract_name.to_string(),
+                    signature: abi::function_signature(abi_item),
+                    abi_item: abi_item.clone(),
+                })
+            })
+            .into_iter()
+            .collect();
+        candidates.extend(self.lookup(&selector).iter().cloned());
+        if candidates.is_empty() {
+            return Err(eyre!("Unknown selector {}", selector));
+        }
+
+        for function in candidates.iter() {
+            let Ok(func) = abi_item_to_function(&function.abi_item) else {
+                continue;
+            };
+            if let Ok(tokens) = func.decode_input(&calldata[4..]) {
+                let args = func
+                    .inputs
+                    .iter()
+                    .zip(tokens.iter())
+                    .map(|(param, token)| format!("{}: {}", param.name, abi::format_token(token)))
+                    .collect();
+                return Ok(DecodedCall {
+                    function: function.clone(),
+                    args,
+                });
+            }
+        }
+        let mut signatures: Vec<String> = candidates
+            .into_iter()
+            .map(|function| function.signature)
+            .collect();
+        signatures.dedup();
+        Err(eyre!("Args don't decode as {}", signatures.join(" or ")))
+    }
+}
+
+// Return data decoded with a function's outputs, `name: value` for named outputs
+pub fn decode_output(abi_item: &Value, return_data: &[u8]) -> Result<String> {
+    let func = abi_item_to_function(abi_item)?;
+    let tokens = func.decode_output(return_data)?;
+    Ok(func
+        .outputs
+        .iter()
+        .zip(tokens.iter())
+        .map(|(param, token)| match param.name.is_empty() {
+            true => abi::format_token(token),
+            false => format!("{}: {}", param.name, abi::format_token(token)),
+        })
+        .collect::<Vec<String>>()
+        .join(", "))
+}
+
+fn abi_item_to_function(abi_item: &Value) -> Result<Function> {
+    let abi: Abi = serde_json::from_value(Value::Array(vec![abi_item.clone()]))?;
+    abi.functions()
+        .next()
+        .cloned()
+        .ok_or_else(|| eyre!("Not a function: {}", abi_item))
+}
+
+// Called whenever a compiled contract, the artifact index or the artifacts' ABIs come in
+pub fn rebuild_registry() {
+    let mut registry = SelectorRegistry::build(
+        STATE.compiled_contracts.read().unwrap().values(),
+        &STATE.artifact_index.read().unwrap(),
+    );
+    let mut current = STATE.selector_registry.write().unwrap();
+    registry.generation = current.generation + 1;
+    *current = registry;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::{ArtifactCode, ArtifactEntry};
+    use serde_json::json;
+
+    fn function(signature: &str) -> Value {
+        let (name, params) = signature.trim_end_matches(')').split_once('(').unwrap();
+        let inputs: Vec<Value> = params
+            .split(',')
+            .filter(|param| !param.is_empty())
+            .enumerate()
+            .map(|(idx, param)| json!({ "name": format!("arg{}", idx), "type": param }))
+            .collect();
+        json!({
+            "type": "function",
+            "name": name,
+            "inputs": inputs,
+            "outputs": [],
+            "stateMutability": "nonpayable",
+        })
+    }
+
+    fn compiled(
+        contract_name: &str,
+        signatures: &[&str],
+        method_identifiers: Value,
+    ) -> CompiledContract {
+        let abi: Vec<Value> = signatures
+            .iter()
+            .map(|signature| function(signature))
+            .collect();
+        CompiledContract::new(
+            format!("out/{0}.sol/{0}.json", contract_name),
+            json!({
+                "abi": abi,
+                "bytecode": { "object": "0x" },
+                "methodIdentifiers": method_identifiers,
+            }),
+        )
+    }
+
+    fn index(artifacts: &[(&str, &[&str])]) -> ArtifactIndex {
+        let mut index = ArtifactIndex::default();
+        for (contract_name, signatures) in artifacts {
+            let artifact_path = format!("out/{0}.sol/{0}.json", contract_name);
+            index.entries.push(ArtifactEntry {
+                contract_name: contract_name.to_string(),
+                source_path: format!("src/{}.sol", contract_name),
+                artifact_path: artifact_path.clone(),
+            });
+            index.codes.push(ArtifactCode {
+                artifact_path,
+                abi: Value::Array(
+                    signatures
+                        .iter()
+                        .map(|signature| function(signature))
+                        .collect(),
+                ),
+                ..Default::default()
+            });
+        }
+        index
+    }
+
+    fn names(functions: &[KnownFunction]) -> Vec<String> {
+        functions
+            .iter()
+            .map(|function| format!("{}.{}", function.contract_name, function.signature))
+            .collect()
+    }
+
+    #[test]
+    fn build_from_method_identifiers_and_artifacts() {
+        let token = compiled(
+            "Token",
+            &[
+                "transfer(address,uint256)",
+                "balanceOf(address)",
+                "mint(uint256)",
+            ],
+            // only what's listed is registered for a compiled contract
+            json!({ "transfer(address,uint256)": "a9059cbb", "balanceOf(address)": "70a08231" }),
+        );
+        let no_identifiers = compiled("Vault", &["balanceOf(address)"], Value::Null);
+        let mut artifacts = index(&[
+            (
+                "IERC20",
+                &["transfer(address,uint256)", "approve(address,uint256)"],
+            ),
+            // the same contract as an artifact
+            (
+                "Token",
+                &["transfer(address,uint256)", "balanceOf(address)"],
+            ),
+        ]);
+        // an artifact that's no longer indexed
+        artifacts.codes.push(ArtifactCode {
+            artifact_path: "out/Gone.sol/Gone.json".to_string(),
+            abi: json!([function("gone()")]),
+            ..Default::default()
+        });
+
+        let registry = SelectorRegistry::build([&token, &no_identifiers].into_iter(), &artifacts);
+        assert_eq!(
+            names(registry.lookup("0xa9059cbb")),
+            vec![
+                "Token.transfer(address,uint256)",
+                "IERC20.transfer(address,uint256)"
+            ]
+        );
+        assert_eq!(
+            names(registry.lookup("0x70a08231")),
+            vec!["Token.balanceOf(address)", "Vault.balanceOf(address)"]
+        );
+        assert_eq!(
+            names(registry.lookup("0x095ea7b3")),
+            vec!["IERC20.approve(address,uint256)"]
+        );
+        assert!(registry
+            .lookup(&abi::function_selector(&function("mint(uint256)")))
+            .is_empty());
+        assert!(registry
+            .lookup(&abi::function_selector(&function("gone()")))
+            .is_empty());
+    }
+
+    #[test]
+    fn overloads_get_their_own_selectors() {
+        let artifacts = index(&[(
+            "NFT",
+            &[
+                "safeTransferFrom(address,address,uint256)",
+                "safeTransferFrom(address,address,uint256,bytes)",
+            ],
+        )]);
+        let registry = SelectorRegistry::build(std::iter::empty(), &artifacts);
+        assert_eq!(
+            names(registry.lookup("0x42842e0e")),
+            vec!["NFT.safeTransferFrom(address,address,uint256)"]
+        );
+        assert_eq!(
+            names(registry.lookup("0xb88d4fde")),
+            vec!["NFT.safeTransferFrom(address,address,uint256,bytes)"]
+        );
+    }
+
+    #[test]
+    fn colliding_selectors() {
+        // both 0x42966c68
+        let token = compiled(
+            "Token",
+            &["burn(uint256)"],
+            json!({ "burn(uint256)": "42966c68" }),
+        );
+        let artifacts = index(&[("Other", &["collate_propagate_storage(bytes16)"])]);
+        let registry = SelectorRegistry::build([&token].into_iter(), &artifacts);
+        assert_eq!(
+            names(registry.lookup("0x42966c68")),
+            vec![
+                "Token.burn(uint256)",
+                "Other.collate_propagate_storage(bytes16)"
+            ]
+        );
+
+        let mut calldata = hex::decode("42966c68").unwrap();
+        calldata.extend([0u8; 31]);
+        calldata.push(1);
+        // the args fit both, so the first known one wins unless the contract's own ABI has it
+        let decoded = registry.decode_calldata(&calldata, None).unwrap();
+        assert_eq!(decoded.to_string(), "Token.burn(arg0: 0x1)");
+        let other_abi = json!([function("collate_propagate_storage(bytes16)")]);
+        let decoded = registry
+            .decode_calldata(&calldata, Some(("Other", &other_abi)))
+            .unwrap();
+        assert_eq!(
+            decoded.to_string(),
+            format!(
+                "Other.collate_propagate_storage(arg0: 0x{})",
+                "00".repeat(16)
+            )
+        );
+
+        let error = registry.decode_calldata(&calldata[..4], None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Args don't decode as burn(uint256) or collate_propagate_storage(bytes16)"
+        );
+        assert!(registry.decode_calldata(&calldata[..3], None).is_err());
+        assert_eq!(
+            registry
+                .decode_calldata(&hex::decode("deadbeef").unwrap(), None)
+                .unwrap_err()
+                .to_string(),
+            "Unknown selector 0xdeadbeef"
+        );
+    }
+}
@@ -1,9 +1,16 @@
 use crate::{
-    components::{CompiledContract, DeployedContract, ReturnAndReceipt},
+    artifacts::ArtifactIndex,
+    components::{
+        CompiledContract, DecodedLog, DeployedContract, LogPage, ReturnAndReceipt, TxRecord, Watch,
+    },
     providers::ClientProviderWrapper,
+    selectors::SelectorRegistry,
 };
 use egui::epaint::ahash::HashMap;
-use ethers::types::{Address, U256};
+use ethers::{
+    signers::LocalWallet,
+    types::{Address, Block, H256, U256, U64},
+};
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
@@ -24,10 +31,13 @@ lazy_static! {
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct SharedState {
     // File data
-    pub open_files: RwLock<Vec<String>>,
+    pub open_files: RwLock<Vec<String>>, // workspace relative paths of open .sol files
+    pub artifact_index: RwLock<ArtifactIndex>,
+    pub selector_registry: RwLock<SelectorRegistry>, // rebuilt from the artifacts, see selectors.rs
     pub file_contents: RwLock<Option<Vec<u8>>>,
     pub completed_compile: RwLock<Option<bool>>,
     pub target_compiled: RwLock<Option<CompiledContract>>,
+    pub compiled_contracts: RwLock<IndexMap<String, CompiledContract>>, // file path -> contract
 
     // VSCode data
     pub vscode_style: RwLock<serde_json::Value>,
@@ -39,13 +49,38 @@ pub struct SharedState {
     pub client: RwLock<Option<ClientProviderWrapper>>,
     pub tx_configs: RwLock<TxConfigs>,
     pub endpoint: RwLock<String>,
+    pub network_profiles: RwLock<Vec<NetworkProfile>>, // persisted by the app
+    pub active_network: RwLock<String>,                // name of the selected profile
+    pub connection: RwLock<ConnectionStatus>,
+    pub connection_id: RwLock<u64>, // bumped on every connect, so stale subscriptions stop
+    pub live_updates: RwLock<bool>, // subscribed to new blocks over ws
+    #[serde(skip)]
+    pub node_identity: RwLock<Option<(H256, U64)>>, // genesis hash + last block, see backend::check_connection
     pub deployed_addresses: RwLock<Vec<String>>,
-    pub deployed_contracts: RwLock<IndexMap<String, DeployedContract>>,
+    pub deployed_contracts: RwLock<IndexMap<String, DeployedContract>>, // on the connected chain
+    pub other_chain_contracts: RwLock<HashMap<u64, IndexMap<String, DeployedContract>>>, // chain id -> contracts
     pub from_addresses: RwLock<Vec<Address>>,
+    #[serde(skip)]
+    pub signers: RwLock<HashMap<Address, LocalWallet>>, // imported keys, see signers::AccountMode
+    #[serde(skip)]
+    pub decrypting_keystore: RwLock<bool>, // a keystore import is deriving its key
+    pub impersonated: RwLock<Vec<Address>>,
     pub temp_fn_output: RwLock<HashMap<String, HashMap<String, ReturnAndReceipt>>>,
     pub func_last_tx: RwLock<HashMap<String, ReturnAndReceipt>>,
+    pub temp_receipt_logs: RwLock<HashMap<String, Vec<DecodedLog>>>, // address -> logs from new receipts
+    pub temp_historical_logs: RwLock<HashMap<String, LogPage>>, // address -> eth_getLogs result
+    pub temp_live_logs: RwLock<HashMap<String, Vec<DecodedLog>>>, // address -> logs from the ws subscription
+    pub temp_storage_values: RwLock<HashMap<String, HashMap<String, String>>>, // address -> variable -> decoded value
+    pub temp_matched_artifacts: RwLock<HashMap<String, CompiledContract>>, // address -> artifact picked for its code
+    pub balances: RwLock<HashMap<Address, U256>>, // refreshed every block while live
+    pub watches: RwLock<Vec<Watch>>,
+    pub tx_history: RwLock<Vec<TxRecord>>, // oldest first, persisted by the app
+    pub snapshots: RwLock<Vec<Snapshot>>,  // oldest first
+    pub latest_block: RwLock<Option<Block<H256>>>,
 
     // Hacky egui globals
+    #[serde(skip)]
+    pub egui_ctx: RwLock<Option<egui::Context>>, // so the backend can trigger a repaint
     pub max_width: RwLock<f32>,
     pub max_collapsable_width: RwLock<f32>,
 }
@@ -56,6 +91,8 @@ impl SharedState {
         Self {
             // endpoint: RwLock::new("https://tinyurl.com/2w7bxmjx".to_string()),
             endpoint: RwLock::new("http://127.0.0.1:8545".to_string()),
+            network_profiles: RwLock::new(vec![NetworkProfile::default()]),
+            active_network: RwLock::new(NetworkProfile::default().name),
             has_focus: RwLock::new(true),
             ..Default::default()
         }
@@ -63,10 +100,14 @@ impl SharedState {
 }
 
 // should this derive default or need to set?
+// Empty gas/fee/nonce strings are left for the node to fill in
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct TxConfigs {
     pub from_address: Address,
-    pub gas_price: U256,
+    pub tx_type: TxType,
+    pub gas_price: String, // legacy + 2930 only
+    pub max_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
     pub gas_limit: String,
     pub value: String,
     pub nonce: String,
@@ -79,7 +120,10 @@ impl Default for TxConfigs {
                 .to_string()
                 .parse()
                 .unwrap(),
-            gas_price: (1e9 as u64).into(),
+            tx_type: TxType::default(),
+            gas_price: "".to_string(),
+            max_fee_per_gas: "".to_string(),
+            max_priority_fee_per_gas: "".to_string(),
             gas_limit: "".to_string(),
             value: "".to_string(),
             nonce: "".to_string(),
@@ -87,6 +131,80 @@ impl Default for TxConfigs {
     }
 }
 
+// A named node to connect to, and what to use on it by default
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub url: String,
+    pub ws_url: String,                // optional, for live updates on new blocks
+    pub chain_id: String,              // expected chain id, empty to accept any
+    pub signer: Option<Address>,       // selected as "from" on connect
+    pub tx_configs: Option<TxConfigs>, // replaces the current configs on connect
+}
+
+impl Default for NetworkProfile {
+    fn default() -> Self {
+        Self {
+            name: "anvil".to_string(),
+            url: "http://127.0.0.1:8545".to_string(),
+            ws_url: "".to_string(),
+            chain_id: "".to_string(),
+            signer: None,
+            tx_configs: None,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Default)]
+pub enum ConnectionStatus {
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected {
+        chain_id: u64,
+    },
+    Failed(String),
+}
+
+impl std::fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStatus::Disconnected => write!(f, "disconnected"),
+            ConnectionStatus::Connecting => write!(f, "connecting"),
+            ConnectionStatus::Connected { chain_id } => write!(f, "connected (chain {})", chain_id),
+            ConnectionStatus::Failed(e) => write!(f, "failed: {}", e),
+        }
+    }
+}
+
+// An evm_snapshot taken through the snapshots panel
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
+pub struct Snapshot {
+    pub id: String, // hex id returned by the node
+    pub label: String,
+    pub block_number: U64,
+    pub taken_at: String, // local time
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum TxType {
+    Legacy,
+    Eip2930,
+    #[default]
+    Eip1559,
+}
+
+impl std::fmt::Display for TxType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxType::Legacy => write!(f, "Legacy"),
+            TxType::Eip2930 => write!(f, "EIP-2930"),
+            TxType::Eip1559 => write!(f, "EIP-1559"),
+        }
+    }
+}
+
 // Helper fns to avoid RwLock syntax
 // Gets a clone
 pub fn read_tx_configs() -> TxConfigs {
@@ -94,6 +212,76 @@ pub fn read_tx_configs() -> TxConfigs {
     tx_configs.clone()
 }
 
+// Oldest entries get dropped past this so persisted state doesn't grow forever
+const MAX_TX_HISTORY: usize = 500;
+
+pub fn push_tx_record(record: TxRecord) {
+    let mut tx_history = STATE.tx_history.write().unwrap();
+    tx_history.push(record);
+    if tx_history.len() > MAX_TX_HISTORY {
+        let excess = tx_history.len() - MAX_TX_HISTORY;
+        tx_history.drain(..excess);
+    }
+}
+
+// ABIs of every deployed contract followed by every compiled artifact we've received;
+// used to decode errors/events that may have been thrown by some other contract
+pub fn read_known_abis() -> Vec<serde_json::Value> {
+    let mut abis: Vec<serde_json::Value> = STATE
+        .deployed_contracts
+        .read()
+        .unwrap()
+        .values()
+        .map(|deployed| deployed.abi.clone())
+        .collect();
+
+    abis.extend(
+        STATE
+            .compiled_contracts
+            .read()
+            .unwrap()
+            .values()
+            .map(|compiled| compiled.abi.clone()),
+    );
+
+    abis
+}
+
+// ABIs of every compiled contract we've received and every indexed artifact. Unlike
+// read_known_abis this doesn't touch deployed_contracts, so it's fine while rendering them.
+pub fn read_artifact_abis() -> Vec<serde_json::Value> {
+    let mut abis: Vec<serde_json::Value> = STATE
+        .compiled_contracts
+        .read()
+        .unwrap()
+        .values()
+        .map(|compiled| compiled.abi.clone())
+        .collect();
+
+    abis.extend(
+        STATE
+            .artifact_index
+            .read()
+            .unwrap()
+            .codes
+            .iter()
+            .map(|artifact_code| artifact_code.abi.clone()),
+    );
+
+    abis
+}
+
+pub fn read_active_network() -> Option<NetworkProfile> {
+    let active_network = STATE.active_network.read().unwrap();
+    STATE
+        .network_profiles
+        .read()
+        .unwrap()
+        .iter()
+        .find(|profile| profile.name == *active_network)
+        .cloned()
+}
+
 // Gets a clone, can borrow if this gets slow
 pub fn read_shared_client() -> Result<ClientProviderWrapper, eyre::Report> {
     let client_guard = STATE.client.read().unwrap();
@@ -0,0 +1,346 @@
+use aes::Aes128;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use ethers::{
+    signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer},
+    types::Address,
+    utils::keccak256,
+};
+use eyre::{eyre, Result};
+use hmac::Hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::{scrypt::Scrypt, shared_state::STATE, wasm};
+
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0";
+// Each account is a full derivation on the UI thread
+pub const MAX_MNEMONIC_ACCOUNTS: u32 = 100;
+
+// How transactions from an account get signed
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AccountMode {
+    Local,        // signed here with an imported key
+    Impersonated, // unsigned, the node fakes the signature (hardhat_impersonateAccount)
+    Node,         // unsigned, the node has to hold the key itself (e.g. geth --dev)
+}
+
+impl std::fmt::Display for AccountMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountMode::Local => write!(f, "local key"),
+            AccountMode::Impersonated => write!(f, "impersonated"),
+            AccountMode::Node => write!(f, "node"),
+        }
+    }
+}
+
+pub fn account_mode(address: Address) -> AccountMode {
+    if STATE.signers.read().unwrap().contains_key(&address) {
+        AccountMode::Local
+    } else if STATE.impersonated.read().unwrap().contains(&address) {
+        AccountMode::Impersonated
+    } else {
+        AccountMode::Node
+    }
+}
+
+// Adds the wallets to the "from" list, replacing any previous mode for their addresses
+pub fn add_wallets(wallets: Vec<LocalWallet>) -> Vec<Address> {
+    let mut signers = STATE.signers.write().unwrap();
+    let mut from_addresses = STATE.from_addresses.write().unwrap();
+    let mut impersonated = STATE.impersonated.write().unwrap();
+
+    let mut added = Vec::new();
+    for wallet in wallets {
+        let address = wallet.address();
+        impersonated.retain(|a| *a != address);
+        if !from_addresses.contains(&address) {
+            from_addresses.push(address);
+        }
+        signers.insert(address, wallet);
+        added.push(address);
+    }
+    added
+}
+
+// `count` accounts starting at `start`, at <derivation_path>/<index>
+pub fn wallets_from_mnemonic(
+    phrase: &str,
+    derivation_path: &str,
+    start: u32,
+    count: u32,
+) -> Result<Vec<LocalWallet>> {
+    if count > MAX_MNEMONIC_ACCOUNTS {
+        return Err(eyre!(
+            "Can import at most {} accounts at a time, not {}",
+            MAX_MNEMONIC_ACCOUNTS,
+            count
+        ));
+    }
+    let derivation_path = match derivation_path.trim() {
+        "" => DEFAULT_DERIVATION_PATH,
+        path => path.trim_end_matches('/'),
+    };
+
+    (start..start.saturating_add(count))
+        .map(|index| {
+            MnemonicBuilder::<English>::default()
+                .phrase(phrase.trim())
+                .derivation_path(&format!("{}/{}", derivation_path, index))?
+                .build()
+                .map_err(|e| eyre!("Invalid mnemonic: {}", e))
+        })
+        .collect()
+}
+
+pub fn wallet_from_private_key(private_key: &str) -> Result<LocalWallet> {
+    let private_key = private_key.trim();
+    private_key
+        .strip_prefix("0x")
+        .unwrap_or(private_key)
+        .parse::<LocalWallet>()
+        .map_err(|e| eyre!("Invalid private key: {}", e))
+}
+
+// Scrypt rounds between giving the event loop a turn, a few ms' worth in the webview
+const SCRYPT_ROUNDS_PER_STEP: usize = 2048;
+
+// Scrypt keystores take a few seconds to derive and the webview has no thread to hand that
+// to, so the derivation is run a step at a time with the UI getting to draw in between.
+// Calls back with the result once done.
+pub fn decrypt_keystore<F>(keystore_json: String, password: String, on_done: F)
+where
+    F: FnOnce(Result<LocalWallet>) + 'static,
+{
+    *STATE.decrypting_keystore.write().unwrap() = true;
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            let mut decryption = KeystoreDecryption::new(&keystore_json, &password)?;
+            while !decryption.step(SCRYPT_ROUNDS_PER_STEP) {
+                let _ = wasm_bindgen_futures::JsFuture::from(wasm::sleep(0)).await;
+            }
+            decryption.finish()
+        }
+        .await;
+        *STATE.decrypting_keystore.write().unwrap() = false;
+        on_done(res);
+    });
+}
+
+// Web3 Secret Storage (v3) keystore, as written by geth/cast/clef, decrypted in one go
+pub fn wallet_from_keystore(keystore_json: &str, password: &str) -> Result<LocalWallet> {
+    let mut decryption = KeystoreDecryption::new(keystore_json, password)?;
+    while !decryption.step(usize::MAX) {}
+    decryption.finish()
+}
+
+// A parsed keystore, decrypted once its key is derived. Same steps as
+// eth_keystore::decrypt_key, which we can't use since it reads the keystore from disk.
+struct KeystoreDecryption {
+    kdf: Kdf,
+    ciphertext: Vec<u8>,
+    mac: Vec<u8>,
+    iv: Vec<u8>,
+}
+
+enum Kdf {
+    Scrypt(Scrypt),
+    Derived(Vec<u8>), // pbkdf2, which is quick enough to derive up front
+}
+
+impl KeystoreDecryption {
+    fn new(keystore_json: &str, password: &str) -> Result<Self> {
+        let keystore: Value = serde_json::from_str(keystore_json)?;
+        let crypto = keystore
+            .get("crypto")
+            .or_else(|| keystore.get("Crypto"))
+            .ok_or(eyre!("Keystore has no crypto section"))?;
+        let kdfparams = &crypto["kdfparams"];
+
+        if crypto["cipher"] != "aes-128-ctr" {
+            return Err(eyre!("Unsupported keystore cipher: {}", crypto["cipher"]));
+        }
+
+        let salt = keystore_hex(&kdfparams["salt"])?;
+        let dklen = kdfparams["dklen"].as_u64().unwrap_or(32) as usize;
+        if dklen < 32 {
+            return Err(eyre!("Unsupported keystore dklen: {}", dklen));
+        }
+
+        let kdf = match crypto["kdf"].as_str() {
+            Some("scrypt") => Kdf::Scrypt(
+                Scrypt::new(
+                    password.as_bytes(),
+                    &salt,
+                    keystore_u32(&kdfparams["n"])?,
+                    keystore_u32(&kdfparams["r"])?,
+                    keystore_u32(&kdfparams["p"])?,
+                    dklen,
+                )
+                .ok_or(eyre!("Invalid keystore scrypt params: {}", kdfparams))?,
+            ),
+            Some("pbkdf2") => {
+                if kdfparams["prf"] != "hmac-sha256" {
+                    return Err(eyre!("Unsupported keystore prf: {}", kdfparams["prf"]));
+                }
+                let rounds = keystore_u32(&kdfparams["c"])?;
+                let mut key = vec![0u8; dklen];
+                pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), &salt, rounds, &mut key);
+                Kdf::Derived(key)
+            }
+            kdf => return Err(eyre!("Unsupported keystore kdf: {:?}", kdf)),
+        };
+
+        Ok(Self {
+            kdf,
+            ciphertext: keystore_hex(&crypto["ciphertext"])?,
+            mac: keystore_hex(&crypto["mac"])?,
+            iv: keystore_hex(&crypto["cipherparams"]["iv"])?,
+        })
+    }
+
+    // Runs up to `rounds` more of the key derivation, true once it's done
+    fn step(&mut self, rounds: usize) -> bool {
+        match &mut self.kdf {
+            Kdf::Scrypt(scrypt) => scrypt.step(rounds),
+            Kdf::Derived(_) => true,
+        }
+    }
+
+    fn finish(self) -> Result<LocalWallet> {
+        let key = match &self.kdf {
+            Kdf::Scrypt(scrypt) => scrypt.key(),
+            Kdf::Derived(key) => key.clone(),
+        };
+
+        // The mac also tells us if the password was wrong
+        let mac = keccak256([&key[16..32], &self.ciphertext[..]].concat());
+        if mac[..] != self.mac[..] {
+            return Err(eyre!("Wrong keystore password"));
+        }
+
+        let mut plaintext = self.ciphertext;
+        let mut cipher = ctr::Ctr128BE::<Aes128>::new_from_slices(&key[..16], &self.iv)
+            .map_err(|_| eyre!("Invalid keystore iv"))?;
+        cipher.apply_keystream(&mut plaintext);
+
+        LocalWallet::from_bytes(&plaintext).map_err(|e| eyre!("Invalid keystore key: {}", e))
+    }
+}
+
+fn keystore_hex(value: &Value) -> Result<Vec<u8>> {
+    let hex_str = value.as_str().ok_or(eyre!("Missing keystore field"))?;
+    Ok(hex::decode(hex_str.trim_start_matches("0x"))?)
+}
+
+fn keystore_u32(value: &Value) -> Result<u32> {
+    value
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or(eyre!("Invalid keystore kdf param: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The test vectors from the Web3 Secret Storage definition, password "testpassword"
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "83dbcc02d8ccb40e466191a123791e0e" },
+            "ciphertext": "d172bf743a674da9cdad04534d56926ef8358534d458fffccd4e6ad2fbde479c",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 262144,
+                "p": 8,
+                "r": 1,
+                "salt": "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+            },
+            "mac": "2103ac29920d71da29f15d75b4a16dbe95cfd7ff8faea1056c33131d846e3097"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    const PRIVATE_KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    fn assert_decrypts(keystore_json: &str) {
+        let wallet = wallet_from_keystore(keystore_json, "testpassword").unwrap();
+        assert_eq!(hex::encode(wallet.signer().to_bytes()), PRIVATE_KEY);
+        assert_eq!(
+            wallet.address(),
+            wallet_from_private_key(PRIVATE_KEY).unwrap().address()
+        );
+    }
+
+    fn assert_wrong_password(keystore_json: &str) {
+        let error = wallet_from_keystore(keystore_json, "testpassw0rd").unwrap_err();
+        assert_eq!(error.to_string(), "Wrong keystore password");
+    }
+
+    #[test]
+    fn pbkdf2_keystore() {
+        assert_decrypts(PBKDF2_KEYSTORE);
+        assert_wrong_password(PBKDF2_KEYSTORE);
+    }
+
+    #[test]
+    fn scrypt_keystore() {
+        assert_decrypts(SCRYPT_KEYSTORE);
+        assert_wrong_password(SCRYPT_KEYSTORE);
+    }
+
+    #[test]
+    fn unsupported_keystores() {
+        let keystore = PBKDF2_KEYSTORE.replace("aes-128-ctr", "aes-128-cbc");
+        assert!(wallet_from_keystore(&keystore, "testpassword").is_err());
+        let keystore = PBKDF2_KEYSTORE.replace("hmac-sha256", "hmac-sha512");
+        assert!(wallet_from_keystore(&keystore, "testpassword").is_err());
+        let keystore = SCRYPT_KEYSTORE.replace("262144", "1000");
+        assert!(wallet_from_keystore(&keystore, "testpassword").is_err());
+        assert!(wallet_from_keystore("{}", "testpassword").is_err());
+    }
+
+    #[test]
+    fn mnemonic_accounts() {
+        let phrase = "test test test test test test test test test test test junk";
+        let wallets = wallets_from_mnemonic(phrase, "", 1, 2).unwrap();
+        let addresses: Vec<String> = wallets
+            .iter()
+            .map(|wallet| format!("{:#x}", wallet.address()))
+            .collect();
+        // anvil's second and third default accounts
+        assert_eq!(
+            addresses,
+            vec![
+                "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc",
+            ]
+        );
+
+        let error = wallets_from_mnemonic(phrase, "", 0, MAX_MNEMONIC_ACCOUNTS + 1).unwrap_err();
+        assert!(error.to_string().starts_with("Can import at most 100"));
+        assert!(wallets_from_mnemonic("not a mnemonic", "", 0, 1).is_err());
+    }
+}
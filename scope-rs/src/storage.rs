@@ -0,0 +1,894 @@
+use crate::{
+    abi, backend,
+    components::CompiledContract,
+    shared_state::{self, STATE},
+};
+use ethers::{
+    abi::{param_type::Reader, ParamType, Token},
+    providers::Middleware,
+    types::{H160, H256, I256, U256},
+    utils::keccak256,
+};
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, future::Future, pin::Pin};
+
+// Only the start of long dynamic arrays/bytes gets read
+const MAX_ARRAY_ELEMENTS: usize = 32;
+const MAX_BYTES_LENGTH: usize = 1024;
+
+// solc's storageLayout output, see
+// https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StorageLayout {
+    #[serde(default)]
+    pub storage: Vec<StorageItem>,
+    #[serde(default)]
+    pub types: Option<HashMap<String, StorageType>>, // null if there are no state variables
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StorageItem {
+    pub label: String,
+    pub slot: String,  // decimal; relative to the struct's slot for members
+    pub offset: usize, // bytes from the right of the slot, for packed variables
+    #[serde(rename = "type")]
+    pub type_id: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageType {
+    pub encoding: String, // inplace, mapping, dynamic_array or bytes
+    pub label: String,
+    pub number_of_bytes: String,
+    pub base: Option<String>,              // array element type
+    pub key: Option<String>,               // mapping key type
+    pub value: Option<String>,             // mapping value type
+    pub members: Option<Vec<StorageItem>>, // struct members
+}
+
+impl StorageLayout {
+    // From the artifact if it was built with the storageLayout output (extra_output in
+    // foundry.toml), otherwise from the build-info
+    pub fn for_compiled(compiled: &CompiledContract) -> Option<Self> {
+        let layout = match compiled.compiled_json.get("storageLayout") {
+            Some(layout) if !layout.is_null() => layout.clone(),
+            _ => {
+                let target = compiled.compiled_json["metadata"]["settings"]["compilationTarget"]
+                    .as_object()?;
+                let (source_path, contract_name) = target.iter().next()?;
+                STATE
+                    .artifact_index
+                    .read()
+                    .unwrap()
+                    .storage_layouts
+                    .get(&format!("{}:{}", source_path, contract_name.as_str()?))?
+                    .clone()
+            }
+        };
+        serde_json::from_value(layout).ok()
+    }
+
+    pub fn type_of(&self, type_id: &str) -> Result<&StorageType> {
+        self.types
+            .as_ref()
+            .and_then(|types| types.get(type_id))
+            .ok_or(eyre!("Unknown storage type {}", type_id))
+    }
+
+    // The type's label, e.g. `mapping(address => uint256)`
+    pub fn type_label(&self, type_id: &str) -> String {
+        self.type_of(type_id)
+            .map_or(type_id.to_string(), |storage_type| {
+                storage_type.label.clone()
+            })
+    }
+}
+
+impl StorageItem {
+    pub fn slot(&self) -> Result<U256> {
+        U256::from_dec_str(&self.slot).map_err(|e| eyre!("Invalid slot {}: {}", self.slot, e))
+    }
+}
+
+impl StorageType {
+    pub fn size(&self) -> Result<usize> {
+        self.number_of_bytes
+            .parse()
+            .map_err(|e| eyre!("Invalid size for {}: {}", self.label, e))
+    }
+
+    // Value types, strings and bytes; everything else is made up of those
+    pub fn is_writable(&self) -> bool {
+        match self.encoding.as_str() {
+            "bytes" => true,
+            "inplace" => self.members.is_none() && self.base.is_none(),
+            _ => false,
+        }
+    }
+
+    fn is_string(&self) -> bool {
+        self.label == "string"
+    }
+
+    // e.g. 3 for `uint256[3]`
+    fn static_array_length(&self) -> Result<usize> {
+        self.label
+            .rsplit_once('[')
+            .and_then(|(_, length)| length.trim_end_matches(']').parse().ok())
+            .ok_or(eyre!("Not a static array: {}", self.label))
+    }
+}
+
+// Reads whole slots and keeps them, so variables packed into the same slot only cost one
+// eth_getStorageAt
+pub struct SlotReader {
+    address: H160,
+    words: HashMap<U256, U256>,
+}
+
+impl SlotReader {
+    pub fn new(address: H160) -> Self {
+        Self {
+            address,
+            words: HashMap::new(),
+        }
+    }
+
+    pub async fn word(&mut self, slot: U256) -> Result<U256> {
+        if let Some(word) = self.words.get(&slot) {
+            return Ok(*word);
+        }
+        let client_wrapper = shared_state::read_shared_client()?;
+        let word = client_wrapper
+            .client
+            .get_storage_at(self.address, to_h256(slot), None)
+            .await?;
+        let word = U256::from_big_endian(word.as_bytes());
+        self.words.insert(slot, word);
+        Ok(word)
+    }
+}
+
+pub fn to_h256(value: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    H256(bytes)
+}
+
+// Where a dynamic array's elements, or a long string/bytes' data, start
+pub fn data_slot(slot: U256) -> U256 {
+    U256::from_big_endian(&keccak256(to_h256(slot)))
+}
+
+// (slot, offset) of element `index` of an array starting at `start`. Elements smaller than a
+// slot are packed, bigger ones (and structs) take up whole slots.
+pub fn element_position(start: U256, element_size: usize, index: usize) -> (U256, usize) {
+    if element_size < 32 {
+        let per_slot = 32 / element_size;
+        (start + index / per_slot, (index % per_slot) * element_size)
+    } else {
+        (start + index * element_size.div_ceil(32), 0)
+    }
+}
+
+fn mask(size: usize) -> U256 {
+    match size {
+        size if size >= 32 => U256::MAX,
+        size => (U256::one() << (size * 8)) - 1,
+    }
+}
+
+// The `size` bytes at `offset` from the right of the slot
+fn extract(word: U256, offset: usize, size: usize) -> U256 {
+    (word >> (offset * 8)) & mask(size)
+}
+
+// Decodes the value of type `type_id` stored at (slot, offset). Boxed since structs and
+// arrays recurse.
+pub fn read_value<'a>(
+    layout: &'a StorageLayout,
+    reader: &'a mut SlotReader,
+    type_id: &'a str,
+    slot: U256,
+    offset: usize,
+) -> Pin<Box<dyn Future<Output = Result<String>> + 'a>> {
+    Box::pin(async move {
+        let storage_type = layout.type_of(type_id)?;
+        match storage_type.encoding.as_str() {
+            // Entries can only be found by key
+            "mapping" => Ok("(mapping)".to_string()),
+            "bytes" => {
+                let (data, length) = read_bytes(reader, slot).await?;
+                let truncated = match data.len() < length {
+                    true => format!("... ({} bytes)", length),
+                    false => String::new(),
+                };
+                match storage_type.is_string() {
+                    true => Ok(format!("{:?}{}", String::from_utf8_lossy(&data), truncated)),
+                    false => Ok(format!("0x{}{}", hex::encode(data), truncated)),
+                }
+            }
+            "dynamic_array" => {
+                let length = reader.word(slot).await?;
+                let base = storage_type.base.as_deref().unwrap_or_default();
+                read_elements(layout, reader, base, data_slot(slot), length).await
+            }
+            _ => match (&storage_type.members, &storage_type.base) {
+                (Some(members), _) => {
+                    let mut values = Vec::new();
+                    for member in members.iter() {
+                        let value = read_value(
+                            layout,
+                            reader,
+                            &member.type_id,
+                            slot + member.slot()?,
+                            member.offset,
+                        )
+                        .await?;
+                        values.push(format!("{}: {}", member.label, value));
+                    }
+                    Ok(format!("{{ {} }}", values.join(", ")))
+                }
+                (None, Some(base)) => {
+                    let length = storage_type.static_array_length()?;
+                    read_elements(layout, reader, base, slot, length.into()).await
+                }
+                (None, None) => {
+                    let word = reader.word(slot).await?;
+                    let size = storage_type.size()?;
+                    Ok(format_value(
+                        storage_type,
+                        extract(word, offset, size),
+                        size,
+                    ))
+                }
+            },
+        }
+    })
+}
+
+async fn read_elements(
+    layout: &StorageLayout,
+    reader: &mut SlotReader,
+    base: &str,
+    start: U256,
+    length: U256,
+) -> Result<String> {
+    let element_size = layout.type_of(base)?.size()?;
+    let shown = length.min(MAX_ARRAY_ELEMENTS.into()).as_usize();
+
+    let mut values = Vec::new();
+    for index in 0..shown {
+        let (slot, offset) = element_position(start, element_size, index);
+        values.push(read_value(layout, reader, base, slot, offset).await?);
+    }
+    if length > shown.into() {
+        values.push(format!("... ({} total)", length));
+    }
+    Ok(format!("[{}]", values.join(", ")))
+}
+
+// Short (< 32 bytes) data lives in the slot itself with length * 2 in the lowest byte,
+// longer data is at keccak(slot) with length * 2 + 1 in the slot.
+// Returns the (possibly truncated) data and the full length.
+async fn read_bytes(reader: &mut SlotReader, slot: U256) -> Result<(Vec<u8>, usize)> {
+    let word = reader.word(slot).await?;
+    if !word.bit(0) {
+        let length = ((word.low_u32() & 0xff) as usize / 2).min(31);
+        return Ok((to_h256(word).as_bytes()[..length].to_vec(), length));
+    }
+
+    let length = (word - 1) / 2;
+    let shown = length.min(MAX_BYTES_LENGTH.into()).as_usize();
+    let start = data_slot(slot);
+    let mut data = Vec::new();
+    for idx in 0..shown.div_ceil(32) {
+        data.extend_from_slice(to_h256(reader.word(start + idx).await?).as_bytes());
+    }
+    data.truncate(shown);
+    // Anything this long is probably a misread layout, so don't try to show it
+    let length = match length > U256::from(u32::MAX) {
+        true => u32::MAX as usize,
+        false => length.as_usize(),
+    };
+    Ok((data, length))
+}
+
+fn format_value(storage_type: &StorageType, value: U256, size: usize) -> String {
+    match storage_type.label.as_str() {
+        "bool" => (!value.is_zero()).to_string(),
+        label if label.starts_with("address") || label.starts_with("contract ") => {
+            format!("{:#x}", H160::from(to_h256(value)))
+        }
+        label if label.starts_with("uint") || label.starts_with("enum ") => value.to_string(),
+        label if label.starts_with("int") => {
+            let sign_bit = U256::one() << (size * 8 - 1);
+            let value = match value & sign_bit == sign_bit {
+                true => value | !mask(size),
+                false => value,
+            };
+            I256::from_raw(value).to_string()
+        }
+        label if label.starts_with("bytes") => {
+            format!("0x{}", hex::encode(&to_h256(value).as_bytes()[32 - size..]))
+        }
+        _ => format!("{:#x}", value),
+    }
+}
+
+// The (slot, value) writes that set a variable to `input`, keeping anything else packed into
+// its slot. Only value types, strings and bytes can be written directly.
+pub async fn encode_write(
+    layout: &StorageLayout,
+    reader: &mut SlotReader,
+    type_id: &str,
+    slot: U256,
+    offset: usize,
+    input: &str,
+) -> Result<Vec<(U256, U256)>> {
+    let storage_type = layout.type_of(type_id)?;
+    if !storage_type.is_writable() {
+        return Err(eyre!(
+            "Can't write {} directly, set its members/elements instead",
+            storage_type.label
+        ));
+    }
+
+    if storage_type.encoding == "bytes" {
+        let data = match storage_type.is_string() {
+            true => input.as_bytes().to_vec(),
+            false => abi::parse_bytes_input(input)?,
+        };
+        return Ok(encode_bytes(slot, &data));
+    }
+
+    let size = storage_type.size()?;
+    let value = parse_value(storage_type, input)? & mask(size);
+    let word = reader.word(slot).await?;
+    let shifted_mask = mask(size) << (offset * 8);
+    Ok(vec![(
+        slot,
+        (word & !shifted_mask) | (value << (offset * 8)),
+    )])
+}
+
+fn encode_bytes(slot: U256, data: &[u8]) -> Vec<(U256, U256)> {
+    if data.len() < 32 {
+        let mut word = [0u8; 32];
+        word[..data.len()].copy_from_slice(data);
+        word[31] = (data.len() * 2) as u8;
+        return vec![(slot, U256::from_big_endian(&word))];
+    }
+
+    let mut writes = vec![(slot, U256::from(data.len() * 2 + 1))];
+    let start = data_slot(slot);
+    for (idx, chunk) in data.chunks(32).enumerate() {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        writes.push((start + idx, U256::from_big_endian(&word)));
+    }
+    writes
+}
+
+// Parses the input the same way as function args, then lays it out like solc packs it
+fn parse_value(storage_type: &StorageType, input: &str) -> Result<U256> {
+    let label = storage_type.label.as_str();
+    match abi::parse_input_to_token(param_type(label)?, input.to_string())? {
+        Token::Uint(value) | Token::Int(value) => Ok(value),
+        Token::Bool(value) => Ok(U256::from(value as u8)),
+        Token::Address(address) => Ok(U256::from_big_endian(address.as_bytes())),
+        Token::FixedBytes(bytes) => Ok(U256::from_big_endian(&bytes)),
+        token => Err(eyre!("Unsupported value for {}: {:?}", label, token)),
+    }
+}
+
+fn param_type(label: &str) -> Result<ParamType> {
+    match label {
+        label if label.starts_with("enum ") => Ok(ParamType::Uint(8)),
+        label if label.starts_with("contract ") || label == "address payable" => {
+            Ok(ParamType::Address)
+        }
+        label => Reader::read(label).map_err(|e| eyre!("Unsupported type {}: {}", label, e)),
+    }
+}
+
+// keccak256(h(key) . slot), where value type keys are padded to 32 bytes like in abi encoding
+// and string/bytes keys are used as is. Same as `cast index`.
+pub fn mapping_slot(key_label: &str, key: &str, slot: U256) -> Result<U256> {
+    let mut preimage = match key_label {
+        "string" => key.as_bytes().to_vec(),
+        "bytes" => abi::parse_bytes_input(key)?,
+        key_label => ethers::abi::encode(&[abi::parse_input_to_token(
+            param_type(key_label)?,
+            key.to_string(),
+        )?]),
+    };
+    preimage.extend_from_slice(to_h256(slot).as_bytes());
+    Ok(U256::from_big_endian(&keccak256(preimage)))
+}
+
+// What indexes into a type next, e.g. "key (address)"; None for value types, strings and bytes
+pub fn key_hint(layout: &StorageLayout, type_id: &str) -> Option<String> {
+    let storage_type = layout.type_of(type_id).ok()?;
+    match (
+        storage_type.encoding.as_str(),
+        &storage_type.members,
+        &storage_type.key,
+    ) {
+        ("mapping", _, Some(key)) => Some(format!("key ({})", layout.type_label(key))),
+        ("dynamic_array", ..) => Some("index".to_string()),
+        ("inplace", Some(members), _) => Some(format!(
+            "member ({})",
+            members
+                .iter()
+                .map(|member| member.label.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        )),
+        ("inplace", None, _) if storage_type.base.is_some() => Some(format!(
+            "index (< {})",
+            storage_type.static_array_length().unwrap_or_default()
+        )),
+        _ => None,
+    }
+}
+
+// Follows the keys from a variable down through mappings (by key), arrays (by index) and
+// structs (by member name), e.g. `balances[0xabc..][3].amount`. Returns where that value
+// lives as an item of its own, so it can be read/written like a top level variable.
+pub fn resolve_path(
+    layout: &StorageLayout,
+    item: &StorageItem,
+    keys: &[String],
+) -> Result<StorageItem> {
+    let mut slot = item.slot()?;
+    let mut offset = item.offset;
+    let mut type_id = item.type_id.clone();
+    let mut label = item.label.clone();
+
+    for key in keys.iter().map(|key| key.trim()) {
+        let storage_type = layout.type_of(&type_id)?;
+        match (
+            storage_type.encoding.as_str(),
+            &storage_type.members,
+            &storage_type.base,
+        ) {
+            ("mapping", ..) => {
+                let key_type = storage_type.key.as_deref().unwrap_or_default();
+                slot = mapping_slot(&layout.type_label(key_type), key, slot)?;
+                offset = 0;
+                type_id = storage_type.value.clone().unwrap_or_default();
+                label = format!("{}[{}]", label, key);
+            }
+            ("inplace", Some(members), _) => {
+                let member = members
+                    .iter()
+                    .find(|member| member.label == key)
+                    .ok_or(eyre!("{} has no member {:?}", storage_type.label, key))?;
+                slot += member.slot()?;
+                offset = member.offset;
+                type_id = member.type_id.clone();
+                label = format!("{}.{}", label, key);
+            }
+            (encoding @ ("dynamic_array" | "inplace"), None, Some(base)) => {
+                let index: usize = key
+                    .parse()
+                    .map_err(|e| eyre!("Invalid index {:?}: {}", key, e))?;
+                let start = match encoding {
+                    "dynamic_array" => data_slot(slot),
+                    _ => {
+                        let length = storage_type.static_array_length()?;
+                        if index >= length {
+                            return Err(eyre!(
+                                "Index {} out of bounds for {}",
+                                index,
+                                storage_type.label
+                            ));
+                        }
+                        slot
+                    }
+                };
+                (slot, offset) = element_position(start, layout.type_of(base)?.size()?, index);
+                type_id = base.clone();
+                label = format!("{}[{}]", label, index);
+            }
+            _ => return Err(eyre!("Can't index into {}", storage_type.label)),
+        }
+    }
+
+    Ok(StorageItem {
+        label,
+        slot: slot.to_string(),
+        offset,
+        type_id,
+    })
+}
+
+// Reads and decodes every variable in the layout, for the deployed contract to pick up
+pub fn read_all(address: H160, layout: StorageLayout) {
+    let items = layout.storage.clone();
+    read_items(address, layout, items);
+}
+
+// Values are posted by label, so resolved mapping entries etc. can be read the same way
+pub fn read_items(address: H160, layout: StorageLayout, items: Vec<StorageItem>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut reader = SlotReader::new(address);
+        let mut values = HashMap::new();
+        for item in items.iter() {
+            let value = match item.slot() {
+                Ok(slot) => {
+                    read_value(&layout, &mut reader, &item.type_id, slot, item.offset).await
+                }
+                Err(e) => Err(e),
+            };
+            values.insert(
+                item.label.clone(),
+                value.unwrap_or_else(|e| format!("error: {}", e)),
+            );
+        }
+
+        STATE
+            .temp_storage_values
+            .write()
+            .unwrap()
+            .entry(format!("{:#x}", address))
+            .or_default()
+            .extend(values);
+    });
+}
+
+// Sets a variable to the typed input via hardhat_setStorageAt, then re-reads it
+pub fn write(address: H160, layout: StorageLayout, item: StorageItem, input: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let res = async {
+            let mut reader = SlotReader::new(address);
+            let writes = encode_write(
+                &layout,
+                &mut reader,
+                &item.type_id,
+                item.slot()?,
+                item.offset,
+                &input,
+            )
+            .await?;
+            for (slot, value) in writes {
+                backend::set_storage(address, to_h256(slot), to_h256(value)).await?;
+            }
+            Ok::<(), eyre::Report>(())
+        }
+        .await;
+
+        match res {
+            Ok(()) => {
+                backend::send_ok_popup(format!("Set {}", item.label));
+                read_items(address, layout, vec![item]);
+            }
+            Err(e) => {
+                backend::send_error_popup(format!("ERROR: {}", e));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DEAD: &str = "0x000000000000000000000000000000000000dead";
+
+    // As solc lays out:
+    //   address owner; bool paused; int8 delta;        // slot 0, packed
+    //   mapping(address => uint256) balances;          // slot 1
+    //   mapping(string => uint256) ids;                // slot 2
+    //   uint128[] values;                              // slot 3
+    //   string name;                                   // slot 4
+    //   uint64[3] fixed;                               // slot 5
+    //   Pos pos;                                       // slots 6-7, struct Pos { uint256 amount; address who; }
+    //   mapping(uint256 => mapping(address => uint256)) nested; // slot 8
+    fn layout() -> StorageLayout {
+        let item = |label: &str, slot: &str, offset: usize, type_id: &str| json!({ "label": label, "slot": slot, "offset": offset, "type": type_id });
+        let value_type = |label: &str, size: &str| json!({ "encoding": "inplace", "label": label, "numberOfBytes": size });
+        serde_json::from_value(json!({
+            "storage": [
+                item("owner", "0", 0, "t_address"),
+                item("paused", "0", 20, "t_bool"),
+                item("delta", "0", 21, "t_int8"),
+                item("balances", "1", 0, "t_mapping(t_address,t_uint256)"),
+                item("ids", "2", 0, "t_mapping(t_string_memory_ptr,t_uint256)"),
+                item("values", "3", 0, "t_array(t_uint128)dyn_storage"),
+                item("name", "4", 0, "t_string_storage"),
+                item("fixed", "5", 0, "t_array(t_uint64)3_storage"),
+                item("pos", "6", 0, "t_struct(Pos)1_storage"),
+                item("nested", "8", 0, "t_mapping(t_uint256,t_mapping(t_address,t_uint256))"),
+            ],
+            "types": {
+                "t_address": value_type("address", "20"),
+                "t_bool": value_type("bool", "1"),
+                "t_int8": value_type("int8", "1"),
+                "t_uint64": value_type("uint64", "8"),
+                "t_uint128": value_type("uint128", "16"),
+                "t_uint256": value_type("uint256", "32"),
+                "t_string_storage": { "encoding": "bytes", "label": "string", "numberOfBytes": "32" },
+                "t_string_memory_ptr": { "encoding": "bytes", "label": "string", "numberOfBytes": "32" },
+                "t_mapping(t_address,t_uint256)": {
+                    "encoding": "mapping",
+                    "label": "mapping(address => uint256)",
+                    "numberOfBytes": "32",
+                    "key": "t_address",
+                    "value": "t_uint256"
+                },
+                "t_mapping(t_string_memory_ptr,t_uint256)": {
+                    "encoding": "mapping",
+                    "label": "mapping(string => uint256)",
+                    "numberOfBytes": "32",
+                    "key": "t_string_memory_ptr",
+                    "value": "t_uint256"
+                },
+                "t_mapping(t_uint256,t_mapping(t_address,t_uint256))": {
+                    "encoding": "mapping",
+                    "label": "mapping(uint256 => mapping(address => uint256))",
+                    "numberOfBytes": "32",
+                    "key": "t_uint256",
+                    "value": "t_mapping(t_address,t_uint256)"
+                },
+                "t_array(t_uint128)dyn_storage": {
+                    "encoding": "dynamic_array",
+                    "label": "uint128[]",
+                    "numberOfBytes": "32",
+                    "base": "t_uint128"
+                },
+                "t_array(t_uint64)3_storage": {
+                    "encoding": "inplace",
+                    "label": "uint64[3]",
+                    "numberOfBytes": "32",
+                    "base": "t_uint64"
+                },
+                "t_struct(Pos)1_storage": {
+                    "encoding": "inplace",
+                    "label": "struct C.Pos",
+                    "numberOfBytes": "64",
+                    "members": [
+                        item("amount", "0", 0, "t_uint256"),
+                        item("who", "1", 0, "t_address"),
+                    ]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn item(layout: &StorageLayout, label: &str) -> StorageItem {
+        layout
+            .storage
+            .iter()
+            .find(|item| item.label == label)
+            .unwrap()
+            .clone()
+    }
+
+    fn slot(hex_str: &str) -> U256 {
+        U256::from_str_radix(hex_str, 16).unwrap()
+    }
+
+    fn reader(words: &[(U256, U256)]) -> SlotReader {
+        SlotReader {
+            address: H160::zero(),
+            words: words.iter().cloned().collect(),
+        }
+    }
+
+    // Everything the tests read is already in the reader, so the futures never wait
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        match future.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("read a slot that wasn't in the reader"),
+        }
+    }
+
+    // Writes `input` to the item, then reads it back from the updated slots
+    fn write_then_read(
+        words: &[(U256, U256)],
+        target: &StorageItem,
+        input: &str,
+    ) -> (SlotReader, String) {
+        let layout = layout();
+        let mut reader = reader(words);
+        let writes = block_on(encode_write(
+            &layout,
+            &mut reader,
+            &target.type_id,
+            target.slot().unwrap(),
+            target.offset,
+            input,
+        ))
+        .unwrap();
+        for (slot, value) in writes {
+            reader.words.insert(slot, value);
+        }
+        let value = block_on(read_value(
+            &layout,
+            &mut reader,
+            &target.type_id,
+            target.slot().unwrap(),
+            target.offset,
+        ))
+        .unwrap();
+        (reader, value)
+    }
+
+    #[test]
+    fn data_slots() {
+        assert_eq!(
+            data_slot(U256::zero()),
+            slot("290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563")
+        );
+        assert_eq!(
+            data_slot(U256::from(3)),
+            slot("c2575a0e9e593c00f959f8c92f12db2869c3395a3b0502d05e2516446f71f85b")
+        );
+    }
+
+    #[test]
+    fn mapping_slots() {
+        assert_eq!(
+            mapping_slot("uint256", "0", U256::zero()).unwrap(),
+            slot("ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5")
+        );
+        assert_eq!(
+            mapping_slot("address", DEAD, U256::one()).unwrap(),
+            slot("b34209a263f6c38fe55f099e9e70f9d67e93982480ff3234a5e0108028ad164d")
+        );
+        // unpadded
+        assert_eq!(
+            mapping_slot("string", "abc", U256::from(2)).unwrap(),
+            slot("31f76c90c4bd232b01bb0bd40689518175171c0bb64d053d77a6e90319d96718")
+        );
+        assert!(mapping_slot("address", "0x1234", U256::one()).is_err());
+    }
+
+    #[test]
+    fn element_positions() {
+        let start = U256::from(10);
+        // 2 uint128s per slot
+        assert_eq!(element_position(start, 16, 0), (start, 0));
+        assert_eq!(element_position(start, 16, 3), (start + 1, 16));
+        // 32 uint8s per slot
+        assert_eq!(element_position(start, 1, 33), (start + 1, 1));
+        // 3 byte elements don't straddle slots, 10 fit in one
+        assert_eq!(element_position(start, 3, 10), (start + 1, 0));
+        // 2 slot structs
+        assert_eq!(element_position(start, 64, 2), (start + 4, 0));
+    }
+
+    #[test]
+    fn resolve_mapping_paths() {
+        let layout = layout();
+        let resolved =
+            resolve_path(&layout, &item(&layout, "balances"), &[DEAD.to_string()]).unwrap();
+        assert_eq!(
+            resolved.slot().unwrap(),
+            slot("b34209a263f6c38fe55f099e9e70f9d67e93982480ff3234a5e0108028ad164d")
+        );
+        assert_eq!(resolved.type_id, "t_uint256");
+        assert_eq!(resolved.label, format!("balances[{}]", DEAD));
+
+        let resolved = resolve_path(
+            &layout,
+            &item(&layout, "nested"),
+            &["7".to_string(), DEAD.to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            resolved.slot().unwrap(),
+            slot("e47dc160219fd2407887a476a97e2876975a0dccd9c425178254c3bec189e956")
+        );
+        assert_eq!(resolved.type_id, "t_uint256");
+    }
+
+    #[test]
+    fn resolve_array_and_struct_paths() {
+        let layout = layout();
+        let resolved = resolve_path(&layout, &item(&layout, "values"), &["3".to_string()]).unwrap();
+        assert_eq!(resolved.slot().unwrap(), data_slot(U256::from(3)) + 1);
+        assert_eq!(resolved.offset, 16);
+        assert_eq!(resolved.label, "values[3]");
+
+        let resolved = resolve_path(&layout, &item(&layout, "fixed"), &["2".to_string()]).unwrap();
+        assert_eq!(resolved.slot().unwrap(), U256::from(5));
+        assert_eq!(resolved.offset, 16);
+        assert!(resolve_path(&layout, &item(&layout, "fixed"), &["3".to_string()]).is_err());
+
+        let resolved = resolve_path(&layout, &item(&layout, "pos"), &["who".to_string()]).unwrap();
+        assert_eq!(resolved.slot().unwrap(), U256::from(7));
+        assert_eq!(resolved.type_id, "t_address");
+        assert_eq!(resolved.label, "pos.who");
+        assert!(resolve_path(&layout, &item(&layout, "pos"), &["nope".to_string()]).is_err());
+
+        assert!(resolve_path(&layout, &item(&layout, "owner"), &["0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn key_hints() {
+        let layout = layout();
+        let hint = |label: &str| key_hint(&layout, &item(&layout, label).type_id);
+        assert_eq!(hint("balances").as_deref(), Some("key (address)"));
+        assert_eq!(hint("values").as_deref(), Some("index"));
+        assert_eq!(hint("fixed").as_deref(), Some("index (< 3)"));
+        assert_eq!(hint("pos").as_deref(), Some("member (amount, who)"));
+        assert_eq!(hint("owner"), None);
+        assert_eq!(hint("name"), None);
+    }
+
+    #[test]
+    fn packed_write_round_trip() {
+        let layout = layout();
+        let owner = slot(DEAD.trim_start_matches("0x"));
+        let words = [(U256::zero(), owner)];
+
+        let (mut reader, paused) = write_then_read(&words, &item(&layout, "paused"), "true");
+        assert_eq!(paused, "true");
+        // the rest of the slot is kept
+        let owner_value = block_on(read_value(
+            &layout,
+            &mut reader,
+            "t_address",
+            U256::zero(),
+            0,
+        ))
+        .unwrap();
+        assert_eq!(owner_value, DEAD);
+
+        // sign extended on the way back
+        let (_, delta) = write_then_read(&words, &item(&layout, "delta"), "-5");
+        assert_eq!(delta, "-5");
+        let (_, delta) = write_then_read(&words, &item(&layout, "delta"), "127");
+        assert_eq!(delta, "127");
+    }
+
+    #[test]
+    fn string_write_round_trip() {
+        let layout = layout();
+        let name = item(&layout, "name");
+
+        // short, in the slot itself
+        let (reader, value) = write_then_read(&[], &name, "hello");
+        assert_eq!(value, "\"hello\"");
+        assert_eq!(reader.words.len(), 1);
+
+        // long, at keccak(slot) with length * 2 + 1 in the slot
+        let long = "a string that's too long to fit in one slot";
+        let (reader, value) = write_then_read(&[], &name, long);
+        assert_eq!(value, format!("{:?}", long));
+        assert_eq!(reader.words[&U256::from(4)], U256::from(long.len() * 2 + 1));
+        assert!(reader.words.contains_key(&(data_slot(U256::from(4)) + 1)));
+
+        // exactly 32 bytes is already long
+        let (reader, value) = write_then_read(&[], &name, &"b".repeat(32));
+        assert_eq!(value, format!("{:?}", "b".repeat(32)));
+        assert_eq!(reader.words[&U256::from(4)], U256::from(65));
+    }
+
+    #[test]
+    fn only_value_types_are_writable() {
+        let layout = layout();
+        let mut reader = reader(&[]);
+        for label in ["balances", "values", "fixed", "pos"] {
+            let target = item(&layout, label);
+            let res = block_on(encode_write(
+                &layout,
+                &mut reader,
+                &target.type_id,
+                target.slot().unwrap(),
+                0,
+                "1",
+            ));
+            assert!(res.is_err(), "{} shouldn't be writable", label);
+        }
+    }
+}
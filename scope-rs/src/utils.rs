@@ -3,12 +3,15 @@ use eyre::{eyre, Result};
 
 use egui::{FontFamily::Monospace, FontId, TextStyle};
 use ethers::{
-    signers::{LocalWallet, MnemonicBuilder},
-    types::U256,
+    abi::ParamType,
+    types::{transaction::eip2718::TypedTransaction, U256},
     utils::parse_ether,
 };
 
-use crate::shared_state;
+use crate::{
+    abi,
+    shared_state::{self, TxConfigs, TxType},
+};
 
 // Queries VSCode for theme colors and uses them to set the egui theme
 // Egui unfortunately currently only supports Color32, so the colors just get clamped
@@ -78,15 +81,6 @@ pub fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
     Some((r, g, b))
 }
 
-pub fn key(index: u32) -> LocalWallet {
-    MnemonicBuilder::<ethers::signers::coins_bip39::English>::default()
-        .phrase("stuff inherit faith park genre spread huge knee ecology private marble supreme")
-        .index(index)
-        .unwrap()
-        .build()
-        .unwrap()
-}
-
 pub fn get_max_width() -> f32 {
     let global_max_width = shared_state::STATE.max_width.read().unwrap();
     *global_max_width
@@ -115,3 +109,216 @@ pub fn eth_str_to_u256_wei(value_string: &str) -> Result<U256> {
         Err(_) => Err(eyre!("Error parsing msg.value {}", value_string)),
     }
 }
+
+// Parses a duration like `90`, `30m`, `12h`, `7d` or `2w` into seconds
+pub fn parse_duration_secs(input: &str) -> Result<u64> {
+    let input = input.trim();
+    let (number, unit_secs) = match input.chars().last() {
+        Some('s') => (&input[..input.len() - 1], 1),
+        Some('m') => (&input[..input.len() - 1], 60),
+        Some('h') => (&input[..input.len() - 1], 60 * 60),
+        Some('d') => (&input[..input.len() - 1], 24 * 60 * 60),
+        Some('w') => (&input[..input.len() - 1], 7 * 24 * 60 * 60),
+        _ => (input, 1),
+    };
+
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit_secs))
+        .ok_or_else(|| eyre!("Invalid duration {:?}", input))
+}
+
+// Applies the tx configs to a deploy/call, converting it to the selected tx type first.
+// Anything left empty (gas, fees, nonce) is filled in by the node when sent.
+pub fn apply_tx_configs(tx: &mut TypedTransaction, tx_configs: &TxConfigs) -> Result<()> {
+    *tx = match tx_configs.tx_type {
+        TxType::Legacy => TypedTransaction::Legacy(tx.clone().into()),
+        TxType::Eip2930 => TypedTransaction::Eip2930(tx.clone().into()),
+        TxType::Eip1559 => TypedTransaction::Eip1559(tx.clone().into()),
+    };
+
+    tx.set_from(tx_configs.from_address);
+    tx.set_value(eth_str_to_u256_wei(&tx_configs.value)?);
+
+    if let Some(gas_limit) = parse_optional_u256(&tx_configs.gas_limit, "gas limit")? {
+        tx.set_gas(gas_limit);
+    }
+    if let Some(nonce) = parse_optional_u256(&tx_configs.nonce, "nonce")? {
+        tx.set_nonce(nonce);
+    }
+
+    match tx {
+        TypedTransaction::Eip1559(inner) => {
+            if let Some(max_fee) = parse_optional_u256(&tx_configs.max_fee_per_gas, "max fee")? {
+                inner.max_fee_per_gas = Some(max_fee);
+            }
+            if let Some(priority_fee) =
+                parse_optional_u256(&tx_configs.max_priority_fee_per_gas, "priority fee")?
+            {
+                inner.max_priority_fee_per_gas = Some(priority_fee);
+            }
+        }
+        _ => {
+            if let Some(gas_price) = parse_optional_u256(&tx_configs.gas_price, "gas price")? {
+                tx.set_gas_price(gas_price);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Parses a uint256 input (with units, e.g. `1.5 gwei`), or None if it's empty
+fn parse_optional_u256(input: &str, field: &str) -> Result<Option<U256>> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+
+    abi::parse_input_to_token(ParamType::Uint(256), input.to_string())
+        .map(|token| token.into_uint())
+        .map_err(|e| eyre!("Invalid {}: {}", field, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Bytes, Eip1559TransactionRequest, H160};
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration_secs("90").unwrap(), 90);
+        assert_eq!(parse_duration_secs("45s").unwrap(), 45);
+        assert_eq!(parse_duration_secs("30m").unwrap(), 30 * 60);
+        assert_eq!(parse_duration_secs(" 12 h ").unwrap(), 12 * 60 * 60);
+        assert_eq!(parse_duration_secs("7d").unwrap(), 7 * 24 * 60 * 60);
+        assert_eq!(parse_duration_secs("2w").unwrap(), 2 * 7 * 24 * 60 * 60);
+        assert_eq!(parse_duration_secs("0").unwrap(), 0);
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for input in ["", "m", "1.5h", "-1", "5y", "h5", "3000000000000000w"] {
+            assert!(parse_duration_secs(input).is_err(), "{:?}", input);
+        }
+    }
+
+    fn configs(tx_type: TxType) -> TxConfigs {
+        TxConfigs {
+            from_address: H160::repeat_byte(0xbb),
+            tx_type,
+            gas_price: "2 gwei".to_string(),
+            max_fee_per_gas: "30 gwei".to_string(),
+            max_priority_fee_per_gas: "1.5 gwei".to_string(),
+            gas_limit: "100000".to_string(),
+            value: "1.5".to_string(),
+            nonce: "7".to_string(),
+        }
+    }
+
+    // What a call or deploy starts out as before the configs are applied
+    fn call() -> TypedTransaction {
+        Eip1559TransactionRequest::new()
+            .to(H160::repeat_byte(0x11))
+            .data(Bytes::from(vec![0xa9, 0x05, 0x9c, 0xbb]))
+            .into()
+    }
+
+    fn gwei(amount: u64) -> U256 {
+        U256::from(amount) * U256::exp10(9)
+    }
+
+    fn assert_common_fields(tx: &TypedTransaction) {
+        assert_eq!(tx.from(), Some(&H160::repeat_byte(0xbb)));
+        assert_eq!(tx.to_addr(), Some(&H160::repeat_byte(0x11)));
+        assert_eq!(
+            tx.data().map(|data| data.to_vec()),
+            Some(vec![0xa9, 0x05, 0x9c, 0xbb])
+        );
+        assert_eq!(tx.value(), Some(&(U256::exp10(18) * 3 / 2)));
+        assert_eq!(tx.gas(), Some(&U256::from(100_000)));
+        assert_eq!(tx.nonce(), Some(&U256::from(7)));
+    }
+
+    #[test]
+    fn legacy_uses_gas_price() {
+        let mut tx = call();
+        apply_tx_configs(&mut tx, &configs(TxType::Legacy)).unwrap();
+        assert_common_fields(&tx);
+        let TypedTransaction::Legacy(inner) = &tx else {
+            panic!("not converted to legacy: {:?}", tx);
+        };
+        assert_eq!(inner.gas_price, Some(gwei(2)));
+    }
+
+    #[test]
+    fn eip2930_uses_gas_price() {
+        let mut tx = call();
+        apply_tx_configs(&mut tx, &configs(TxType::Eip2930)).unwrap();
+        assert_common_fields(&tx);
+        let TypedTransaction::Eip2930(inner) = &tx else {
+            panic!("not converted to 2930: {:?}", tx);
+        };
+        assert_eq!(inner.tx.gas_price, Some(gwei(2)));
+        assert!(inner.access_list.0.is_empty());
+    }
+
+    #[test]
+    fn eip1559_uses_fees() {
+        let mut tx = TypedTransaction::Legacy(call().into());
+        apply_tx_configs(&mut tx, &configs(TxType::Eip1559)).unwrap();
+        assert_common_fields(&tx);
+        let TypedTransaction::Eip1559(inner) = &tx else {
+            panic!("not converted to 1559: {:?}", tx);
+        };
+        assert_eq!(inner.max_fee_per_gas, Some(gwei(30)));
+        assert_eq!(inner.max_priority_fee_per_gas, Some(gwei(3) / 2));
+    }
+
+    #[test]
+    fn empty_configs_are_left_to_the_node() {
+        for tx_type in [TxType::Legacy, TxType::Eip2930, TxType::Eip1559] {
+            let mut tx = call();
+            let tx_configs = TxConfigs {
+                tx_type,
+                ..Default::default()
+            };
+            apply_tx_configs(&mut tx, &tx_configs).unwrap();
+            assert_eq!(tx.gas(), None);
+            assert_eq!(tx.nonce(), None);
+            assert_eq!(tx.gas_price(), None);
+            assert_eq!(tx.value(), Some(&U256::zero()));
+        }
+    }
+
+    #[test]
+    fn invalid_configs() {
+        let mut tx_configs = configs(TxType::Legacy);
+        tx_configs.gas_price = "cheap".to_string();
+        let error = apply_tx_configs(&mut call(), &tx_configs).unwrap_err();
+        assert!(
+            error.to_string().starts_with("Invalid gas price"),
+            "{}",
+            error
+        );
+
+        let mut tx_configs = configs(TxType::Eip1559);
+        tx_configs.max_fee_per_gas = "-1".to_string();
+        let error = apply_tx_configs(&mut call(), &tx_configs).unwrap_err();
+        assert!(
+            error.to_string().starts_with("Invalid max fee"),
+            "{}",
+            error
+        );
+
+        // only checked for the tx type that uses it
+        let mut tx_configs = configs(TxType::Eip1559);
+        tx_configs.gas_price = "cheap".to_string();
+        assert!(apply_tx_configs(&mut call(), &tx_configs).is_ok());
+
+        let mut tx_configs = configs(TxType::Eip1559);
+        tx_configs.value = "lots".to_string();
+        assert!(apply_tx_configs(&mut call(), &tx_configs).is_err());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{backend, components::CompiledContract, shared_state::STATE};
+use crate::{
+    artifacts::ArtifactIndex, backend, components::CompiledContract, selectors, shared_state::STATE,
+};
 use js_sys::{Array, Date};
 use serde_json::Value;
 use wasm_bindgen::prelude::*;
@@ -19,21 +21,56 @@ pub fn receive_vscode_style(received_style: JsValue) {
     log!("{:?}", STATE.vscode_style.read().unwrap());
 }
 
-// Receive the .sol file paths of the files currently open in the editor
+// Receive the (workspace relative) .sol file paths of the files currently open in the editor
 #[wasm_bindgen]
 pub fn receive_open_file_paths(js_filepaths: &Array) {
     log!("`.receive_open_files");
-    // Convert the js Array to a rust String vec
-    let length = js_filepaths.length();
+    let rust_strings = js_array_to_strings(js_filepaths);
+    log!("{:?}", rust_strings);
+    *STATE.open_files.write().unwrap() = rust_strings;
+}
+
+// Receive every artifact path in the output dir(s) + the trimmed build-info files and any
+// storage layouts in them
+#[wasm_bindgen]
+pub fn receive_artifact_files(
+    js_artifact_paths: &Array,
+    js_build_infos: &Array,
+    js_storage_layouts: &Array,
+) {
+    log!("`.receive_artifact_files");
+    let artifact_index = ArtifactIndex::build(
+        js_array_to_strings(js_artifact_paths),
+        js_array_to_strings(js_build_infos),
+        js_array_to_strings(js_storage_layouts),
+    );
+    log!("indexed {} artifacts", artifact_index.entries.len());
+    *STATE.artifact_index.write().unwrap() = artifact_index;
+    selectors::rebuild_registry();
+}
+
+// Receive the runtime code + ABI of the indexed artifacts, asked for on demand
+#[wasm_bindgen]
+pub fn receive_artifact_codes(js_artifact_codes: &Array) {
+    log!("`.receive_artifact_codes");
+    STATE
+        .artifact_index
+        .write()
+        .unwrap()
+        .set_codes(js_array_to_strings(js_artifact_codes));
+    selectors::rebuild_registry();
+}
+
+// Convert a js Array to a rust String vec, skipping anything that isn't a string
+fn js_array_to_strings(js_array: &Array) -> Vec<String> {
     let mut rust_strings = Vec::<String>::new();
 
-    for i in 0..length {
-        if let Some(string) = js_filepaths.get(i).as_string() {
+    for i in 0..js_array.length() {
+        if let Some(string) = js_array.get(i).as_string() {
             rust_strings.push(string);
         }
     }
-    log!("{:?}", rust_strings);
-    *STATE.open_files.write().unwrap() = rust_strings;
+    rust_strings
 }
 
 // for receiving raw source code
@@ -49,18 +86,47 @@ pub fn receive_compiled_solidity(sol_json_string: JsValue, file_path: String) {
     let json_str = sol_json_string.as_string().unwrap();
     let parsed_json: Value = serde_json::from_str(&json_str).unwrap();
 
-    let compiled = CompiledContract::new(file_path, parsed_json);
+    let compiled = CompiledContract::new(file_path.clone(), parsed_json);
     *STATE.target_compiled.write().unwrap() = Some(compiled.clone());
+
+    // Keep every artifact we've seen around for decoding errors etc.
+    STATE
+        .compiled_contracts
+        .write()
+        .unwrap()
+        .insert(file_path, compiled);
+    selectors::rebuild_registry();
     // log!("{:?}", STATE.target_compiled.read().unwrap());
 
     // write filepath to shared storage
     // *STATE.compiled_sol_file_path.write().unwrap() = Some(file_path);
 }
 
+// An artifact loaded for a deployed contract; unlike receive_compiled_solidity this doesn't
+// change the selected contract
+#[wasm_bindgen]
+pub fn receive_artifact(artifact_json_string: JsValue, file_path: String) {
+    log!("in backend.receive_artifact");
+    let json_str = artifact_json_string.as_string().unwrap_or_default();
+    match serde_json::from_str::<Value>(&json_str) {
+        Ok(parsed_json) => {
+            let compiled = CompiledContract::new(file_path.clone(), parsed_json);
+            STATE
+                .compiled_contracts
+                .write()
+                .unwrap()
+                .insert(file_path, compiled);
+            selectors::rebuild_registry();
+        }
+        Err(e) => log!("couldn't parse artifact {}: {}", file_path, e),
+    }
+}
+
 #[wasm_bindgen]
 pub fn handle_completed_forge_build() {
     *STATE.completed_compile.write().unwrap() = Some(true);
     get_open_files();
+    get_artifact_files();
 }
 
 #[wasm_bindgen]
@@ -87,6 +153,9 @@ pub fn handle_file_opened_or_closed() {
 #[wasm_bindgen(module = "/src/bridge.js")]
 extern "C" {
     pub fn get_open_files();
+    pub fn get_artifact_files();
+    pub fn get_artifact_codes(artifact_paths: &Array);
+    pub fn get_artifact(file_path: String);
     pub fn get_file_contents(file_path: String);
     pub fn get_compiled_solidity(file_path: String);
     pub fn execute_shell_command(command: String);
@@ -94,4 +163,5 @@ extern "C" {
     pub fn send_error_to_vscode(error_text: String);
     pub fn send_ok_to_vscode(error_text: String);
     pub fn get_vscode_style() -> JsValue;
+    pub fn sleep(ms: u32) -> js_sys::Promise;
 }